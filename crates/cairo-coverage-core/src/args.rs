@@ -1,15 +1,157 @@
+use crate::progress::ProgressCallback;
+use camino::Utf8PathBuf;
+
 /// Options accepted by `cairo_coverage_core` `run` function.
 #[derive(Default, Clone)]
+#[non_exhaustive]
 pub struct RunOptions {
+    /// Options deciding which statements are included in the report.
+    pub filter: FilterOptions,
+
+    /// Options deciding what the report contains.
+    pub report: ReportOptions,
+
+    /// If set, traces and programs that cannot be processed are skipped instead of failing the whole run.
+    pub keep_going: bool,
+
+    /// If set, source files that changed after the program was built fail the run instead of being warned about.
+    /// Traces generated before the program was rebuilt are always only warned about, as the program may be unchanged.
+    pub strict: bool,
+
+    /// Directory against which relative `source_sierra_path` entries from the traces are resolved first.
+    /// If not found there, the directory of the trace file and the current working directory are tried.
+    pub sierra_root: Option<Utf8PathBuf>,
+
+    /// Rules for rewriting source file paths baked into the programs at compile time.
+    /// The first matching rule is applied before any filtering.
+    pub path_prefix_maps: Vec<PathPrefixMap>,
+
+    /// If set, source files inside the project directory are reported with paths relative to it.
+    pub relative_paths: bool,
+
+    /// Root directories of the workspace members.
+    /// Source files inside them are included in the report, just like the ones inside the project directory.
+    pub workspace_member_paths: Vec<Utf8PathBuf>,
+
+    /// Root directories of the dependencies, e.g. inside Scarb's cache.
+    /// Source files inside them are never included in the report, even if they are inside the project directory.
+    pub dependency_paths: Vec<Utf8PathBuf>,
+
+    /// Traces written directly by the Cairo VM, processed in addition to the trace files.
+    pub vm_traces: Vec<VmTrace>,
+
+    /// Callback notified about the progress of the computation and the time spent in each stage.
+    pub progress_callback: Option<ProgressCallback>,
+}
+
+/// Options of [`RunOptions`] deciding which statements are included in the report.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct FilterOptions {
     /// Include additional components in the coverage report.
     pub include: Vec<IncludedComponent>,
 
+    /// Policy deciding which libfuncs are unreliable, i.e. whose statements are excluded from the report.
+    pub libfunc_policy: LibfuncPolicy,
+
+    /// If set, statements detected as non-executable in the compiled program are excluded from the report:
+    /// the ones compiled to no `casm` instructions and the ones mapped to many different lines.
+    pub exclude_non_executable: bool,
+}
+
+/// Options of [`RunOptions`] deciding what the report contains.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ReportOptions {
     /// If set, the hit count of the lines will not be truncated to 1.
     pub no_truncation: bool,
+
+    /// How the hit count of the lines is computed.
+    pub hit_count_mode: HitCountMode,
+
+    /// If set, a separate LCOV report is generated for each contract (or test executable),
+    /// containing only the code executed through it.
+    pub split_by_contract: bool,
+
+    /// If set, the coverage of every Sierra statement is collected in [`Coverage::statements`](crate::model::Coverage::statements),
+    /// e.g. for the [`SierraReporter`](crate::output::reporter::SierraReporter).
+    pub sierra_statements: bool,
+}
+
+/// Trace written directly by the Cairo VM together with the program that was executed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VmTrace {
+    /// Binary relocated trace, e.g. written with the `--trace_file` option of `cairo1-run`.
+    /// Only the `pc` of the entries is used, so the memory written by the Cairo VM is not needed.
+    pub trace: Utf8PathBuf,
+    /// Sierra program (with debug info) that was compiled and executed. CASM programs are not supported,
+    /// as they cannot be mapped to the source code.
+    pub program: Utf8PathBuf,
+    /// Offset of the program in the executed CASM code of this trace, i.e. the size of the header preceding it.
+    /// If not set, the program is assumed to be wrapped in a header that ends the execution,
+    /// as done e.g. by `cairo1-run`.
+    pub program_offset: Option<usize>,
+}
+
+/// Ways of computing the hit count of the lines.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum HitCountMode {
+    /// Number of times the execution entered the line, i.e. executed it after executing a different line.
+    /// It does not depend on the number of statements generated for the line, so it is stable across Scarb versions.
+    #[default]
+    LineEntries,
+    /// Sum of the execution counts of the statements generated for the line.
+    /// It depends on the compiler, so it varies between Scarb versions.
+    Statements,
+}
+
+/// Policy deciding which libfuncs are unreliable. Statements invoking them are excluded from the report,
+/// as they are often generated for lines that were not executed.
+/// Patterns may contain `*` wildcards matching any sequence of characters, e.g. `struct_*`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LibfuncPolicy {
+    /// Base set of unreliable libfuncs.
+    pub preset: LibfuncPreset,
+    /// Libfunc names or patterns treated as unreliable in addition to the preset.
+    pub unreliable: Vec<String>,
+    /// Libfunc names or patterns treated as reliable, even if they are in the preset or match `unreliable`.
+    pub reliable: Vec<String>,
+}
+
+/// Base sets of unreliable libfuncs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum LibfuncPreset {
+    /// Libfuncs like `store_temp` or `drop` that are generated for many lines, including the not executed ones.
+    #[default]
+    Default,
+    /// No libfuncs are unreliable, every statement is counted.
+    Strict,
+}
+
+/// Line of a source file explained by [`crate::explain`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceLine {
+    /// Path of the source file. It is matched against the end of the source file paths, e.g. `src/lib.cairo`.
+    pub file: Utf8PathBuf,
+    /// Line number, 1-based.
+    pub line: usize,
+}
+
+/// Rule replacing the `from` prefix of a source file path with `to`.
+/// Prefixes are matched on whole path components.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PathPrefixMap {
+    /// Prefix of the paths as they appear in the program, e.g. `/builds/project`.
+    pub from: Utf8PathBuf,
+    /// Prefix it is replaced with, e.g. `/home/user/project`.
+    pub to: Utf8PathBuf,
 }
 
 /// Additional components that can be included in the coverage report.
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum IncludedComponent {
     // TODO(#188): currently there is no way to include test functions in the coverage report,
     // as this option is unavailable in the `cairo-coverage` CLI,
@@ -1,58 +1,101 @@
+use crate::args::HitCountMode;
+use crate::build::executed_statement_count;
 use crate::build::executed_statement_count::ExecutedStatementCount;
-use crate::build::filter::statement_category_filter::StatementCategoryFilter;
-use crate::build::statement_information::StatementInformationMap;
-use crate::build::{executed_statement_count, statement_information};
-use crate::loading::enriched_program::EnrichedProgram;
-use crate::loading::execution_data::ExecutionData;
+use crate::build::statement_information::{ExcludedStatementMap, StatementInformationMap};
+use crate::loading::execution_infos::CallExecution;
 use anyhow::Result;
 use cairo_lang_sierra::program::Program;
 use cairo_lang_sierra_to_casm::compiler::{CairoProgramDebugInfo, SierraToCasmConfig};
 use cairo_lang_sierra_to_casm::metadata::{MetadataComputationConfig, calc_metadata};
 use cairo_lang_sierra_type_size::ProgramRegistryInfo;
+use camino::Utf8Path;
+use itertools::Itertools;
 
 /// All necessary data for the coverage analysis.
 #[derive(Clone)]
 pub struct CoverageInput {
     pub executed_statement_count: ExecutedStatementCount,
+    /// Counts of the statements from which the hit counts of the lines are computed,
+    /// depending on the [`HitCountMode`].
+    pub line_hit_count: ExecutedStatementCount,
     pub statement_information_map: StatementInformationMap,
+    /// Statements with code locations that were rejected by the filter.
+    pub excluded_statement_map: ExcludedStatementMap,
+    /// Number of statements in the program.
+    pub statement_count: usize,
 }
 
-/// Build the [`CoverageInput`].
-/// This involves compiling the program to `casm`, which is a costly operation.
-/// # Panics
-/// - panics if the program cannot be compiled to `casm`.
+/// Build the [`CoverageInput`] from the [`CallExecution`]s of the program at `source_sierra_path`,
+/// its [`CairoProgramDebugInfo`] and its statements split by the filter,
+/// see [`statement_information::build_map`](crate::build::statement_information::build_map).
 pub fn build(
-    ExecutionData {
-        casm_level_infos,
-        enriched_program:
-            EnrichedProgram {
-                program,
-                coverage_annotations,
-                profiler_annotations,
-                ..
-            },
-    }: ExecutionData,
-    filter: &StatementCategoryFilter,
+    source_sierra_path: &Utf8Path,
+    call_executions: &[CallExecution],
+    statement_count: usize,
+    (statement_information_map, excluded_statement_map): (
+        StatementInformationMap,
+        ExcludedStatementMap,
+    ),
+    casm_debug_info: &CairoProgramDebugInfo,
+    hit_count_mode: HitCountMode,
 ) -> CoverageInput {
-    let casm_debug_info = compile(&program).expect("failed to compile program to casm");
-
-    let statement_information_map =
-        statement_information::build_map(coverage_annotations, profiler_annotations, filter);
-
-    let executed_statement_count = executed_statement_count::build(
-        &casm_level_infos,
-        &casm_debug_info,
+    log_statement_counts(
+        source_sierra_path,
         &statement_information_map,
+        &excluded_statement_map,
     );
 
+    let executed_statement_ids =
+        executed_statement_count::build_ids(call_executions, casm_debug_info);
+    let executed_statement_count =
+        executed_statement_count::build(&executed_statement_ids, &statement_information_map);
+    let line_hit_count = match hit_count_mode {
+        HitCountMode::LineEntries => executed_statement_count::build_line_entries(
+            &executed_statement_ids,
+            &statement_information_map,
+        ),
+        HitCountMode::Statements => executed_statement_count.clone(),
+    };
+
     CoverageInput {
         executed_statement_count,
+        line_hit_count,
         statement_information_map,
+        excluded_statement_map,
+        statement_count,
+    }
+}
+
+/// Log how many statements are included in the report and how many were excluded for each
+/// [`ExclusionReason`](crate::build::filter::statement_category_filter::ExclusionReason).
+fn log_statement_counts(
+    source_sierra_path: &Utf8Path,
+    statement_information_map: &StatementInformationMap,
+    excluded_statement_map: &ExcludedStatementMap,
+) {
+    log::debug!(
+        "{source_sierra_path}: {} statement(s) included in the report",
+        statement_information_map.len()
+    );
+    for (reason, count) in excluded_statement_map
+        .values()
+        .map(|excluded_statement| excluded_statement.reason)
+        .counts()
+        .into_iter()
+        .sorted_by_key(|(reason, _)| reason.name())
+    {
+        log::debug!(
+            "{source_sierra_path}: {count} statement(s) excluded as {}",
+            reason.name()
+        );
     }
 }
 
 /// Compile the given [`Program`] to `casm` and return the [`CairoProgramDebugInfo`].
-fn compile(program: &Program) -> Result<CairoProgramDebugInfo> {
+/// This is a costly operation.
+/// # Errors
+/// - fails if the program cannot be compiled to `casm`.
+pub fn compile(program: &Program) -> Result<CairoProgramDebugInfo> {
     let program_registry_info = ProgramRegistryInfo::new(program)?;
     let casm = cairo_lang_sierra_to_casm::compiler::compile(
         program,
@@ -69,3 +112,19 @@ fn compile(program: &Program) -> Result<CairoProgramDebugInfo> {
     )?;
     Ok(casm.debug_info)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_lang_sierra::ProgramParser;
+
+    #[test]
+    fn test_compile_fails_for_invalid_program() {
+        // The function uses a type that is never declared, so the program registry cannot be built.
+        let program = ProgramParser::new()
+            .parse("foo@0(a: felt252) -> ();")
+            .unwrap();
+
+        assert!(compile(&program).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::build::statement_information::StatementInformationMap;
+use crate::loading::execution_infos::CallExecution;
 use cairo_annotations::map_pcs_to_sierra_statement_ids;
-use cairo_annotations::trace_data::CasmLevelInfo;
 use cairo_lang_sierra::program::StatementIdx;
 use cairo_lang_sierra_to_casm::compiler::CairoProgramDebugInfo;
 use itertools::Itertools;
@@ -10,34 +10,37 @@ use std::collections::HashMap;
 /// Sierra statement IDs that were executed and their count.
 pub type ExecutedStatementCount = HashMap<StatementIdx, usize>;
 
-/// Build the executed statement count.
-/// This involves:
-/// - mapping casm level info to sierra statement IDs
-/// - deduplicating the IDs
-pub fn build(
-    casm_level_infos: &[CasmLevelInfo],
+/// Sierra statement IDs executed by each call execution, in the execution order.
+pub type ExecutedStatementIds = Vec<Vec<StatementIdx>>;
+
+/// Build the [`ExecutedStatementIds`] by mapping casm level info of each call execution to sierra statement IDs.
+/// The other builders in this module derive the counts from them.
+pub fn build_ids(
+    call_executions: &[CallExecution],
     casm_debug_info: &CairoProgramDebugInfo,
-    statement_information_map: &StatementInformationMap,
-) -> ExecutedStatementCount {
-    let executed_statement_ids = casm_level_infos
+) -> ExecutedStatementIds {
+    call_executions
         .par_iter()
-        .flat_map(|casm_level_info| {
-            map_pcs_to_sierra_statement_ids(casm_debug_info, casm_level_info)
+        .map(|call_execution| {
+            map_pcs_to_sierra_statement_ids(casm_debug_info, &call_execution.casm_level_info)
+                .into_iter()
+                .filter_map(Option::from)
+                .collect()
         })
-        .filter_map(Option::from)
-        .collect();
-
-    deduplicate(executed_statement_ids, statement_information_map)
+        .collect()
 }
 
-/// Deduplicate the executed statement IDs and that are neighboring.
-/// As from one line, many statements can be generated. We want to count the line only once.
-fn deduplicate(
-    iter: Vec<StatementIdx>,
+/// Build the executed statement count.
+/// The executed statement IDs that are neighboring are deduplicated,
+/// as from one line, many statements can be generated.
+pub fn build(
+    executed_statement_ids: &ExecutedStatementIds,
     statement_information_map: &StatementInformationMap,
 ) -> ExecutedStatementCount {
-    iter.into_iter()
-        .fold(Vec::new(), |mut acc, statement_idx| {
+    executed_statement_ids
+        .iter()
+        .flatten()
+        .fold(Vec::new(), |mut acc, &statement_idx| {
             if points_to_different_statement(statement_information_map, acc.last(), statement_idx) {
                 acc.push(statement_idx);
             }
@@ -47,6 +50,46 @@ fn deduplicate(
         .counts()
 }
 
+/// Build the executed statement count without deduplication,
+/// i.e. the number of executed `casm` instructions mapped to each statement.
+pub fn build_raw(executed_statement_ids: &ExecutedStatementIds) -> ExecutedStatementCount {
+    executed_statement_ids.iter().flatten().copied().counts()
+}
+
+/// Build the number of line entries started by each statement.
+/// A line is entered when a statement of the report is executed after statements of a different line
+/// (or of the same line in a different function) or at the start of a call execution.
+/// Statements excluded from the report are skipped, so they do not interrupt the execution of a line.
+///
+/// Unlike the executed statement count, it does not depend on how many statements the compiler
+/// generates for a line, which makes it stable across compiler versions.
+pub fn build_line_entries(
+    executed_statement_ids: &ExecutedStatementIds,
+    statement_information_map: &StatementInformationMap,
+) -> ExecutedStatementCount {
+    let mut line_entries = ExecutedStatementCount::new();
+
+    for statement_ids in executed_statement_ids {
+        let mut current_line = None;
+        for statement_idx in statement_ids {
+            let Some(statement_information) = statement_information_map.get(statement_idx) else {
+                continue;
+            };
+            let line = Some((
+                &statement_information.source_file_full_path,
+                &statement_information.function_name,
+                &statement_information.line_range.start,
+            ));
+            if line != current_line {
+                current_line = line;
+                *line_entries.entry(*statement_idx).or_default() += 1;
+            }
+        }
+    }
+
+    line_entries
+}
+
 fn points_to_different_statement(
     statement_information_map: &StatementInformationMap,
     last_idx: Option<&StatementIdx>,
@@ -56,3 +99,138 @@ fn points_to_different_statement(
         statement_information_map.get(last_idx) != statement_information_map.get(&current_idx)
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build::statement_information::{LineRange, StatementInformation};
+    use cairo_annotations::annotations::coverage::{LineNumber, SourceFileFullPath};
+    use cairo_annotations::annotations::profiler::FunctionName;
+
+    /// Statement information map with the statements `0..` mapped to the given lines of `pkg::f`.
+    fn statement_information_map(lines: &[usize]) -> StatementInformationMap {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(idx, &line)| {
+                let statement_information = StatementInformation {
+                    idx: StatementIdx(idx),
+                    function_name: FunctionName("pkg::f".to_string()),
+                    source_file_full_path: SourceFileFullPath("src/lib.cairo".to_string()),
+                    line_range: LineRange {
+                        start: LineNumber(line),
+                        end: LineNumber(line),
+                    },
+                };
+                (StatementIdx(idx), statement_information)
+            })
+            .collect()
+    }
+
+    fn ids(ids: &[usize]) -> Vec<StatementIdx> {
+        ids.iter().copied().map(StatementIdx).collect()
+    }
+
+    /// Sums the counts of the statements of each line, as it is done in the report.
+    fn line_counts(
+        counts: &ExecutedStatementCount,
+        statement_information_map: &StatementInformationMap,
+    ) -> Vec<(usize, usize)> {
+        counts
+            .iter()
+            .map(|(idx, count)| (statement_information_map[idx].line_range.start.0, *count))
+            .into_grouping_map()
+            .sum()
+            .into_iter()
+            .sorted()
+            .collect()
+    }
+
+    #[test]
+    fn test_line_entries_count_loop_iterations() {
+        // Line 1 runs once, then lines 2 and 3 run in a loop three times.
+        let map = statement_information_map(&[1, 2, 2, 3]);
+        let executed = vec![ids(&[0, 1, 2, 3, 1, 2, 3, 1, 2, 3])];
+
+        let line_entries = build_line_entries(&executed, &map);
+
+        assert_eq!(
+            line_counts(&line_entries, &map),
+            vec![(1, 1), (2, 3), (3, 3)]
+        );
+    }
+
+    #[test]
+    fn test_line_entries_do_not_depend_on_statements_per_line() {
+        // The same execution compiled by two compiler versions generating a different number of statements.
+        let map_a = statement_information_map(&[1, 2, 3]);
+        let executed_a = vec![ids(&[0, 1, 2, 1, 2])];
+        let map_b = statement_information_map(&[1, 1, 1, 2, 2, 2, 3]);
+        let executed_b = vec![ids(&[0, 1, 2, 3, 4, 5, 6, 3, 4, 5, 6])];
+
+        assert_eq!(
+            line_counts(&build_line_entries(&executed_a, &map_a), &map_a),
+            line_counts(&build_line_entries(&executed_b, &map_b), &map_b)
+        );
+        assert_ne!(
+            line_counts(&build(&executed_a, &map_a), &map_a),
+            line_counts(&build(&executed_b, &map_b), &map_b)
+        );
+    }
+
+    #[test]
+    fn test_line_entries_skip_excluded_statements_and_reset_between_executions() {
+        let mut map = statement_information_map(&[1, 2, 1]);
+        // Statement 2 is excluded from the report.
+        map.remove(&StatementIdx(2));
+        let executed = vec![ids(&[0, 2, 0, 1]), ids(&[1])];
+
+        let line_entries = build_line_entries(&executed, &map);
+
+        assert_eq!(line_counts(&line_entries, &map), vec![(1, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn test_statements_count_every_statement_of_a_line() {
+        // Line 1 is compiled to two statements, the second one to three `casm` instructions.
+        let map = statement_information_map(&[1, 1, 2]);
+        let executed = vec![ids(&[0, 1, 1, 1, 2]), ids(&[0, 1, 1, 1, 2])];
+
+        assert_eq!(
+            line_counts(&build_raw(&executed), &map),
+            vec![(1, 8), (2, 2)]
+        );
+        assert_eq!(
+            line_counts(&build(&executed, &map), &map),
+            vec![(1, 4), (2, 2)]
+        );
+        assert_eq!(
+            line_counts(&build_line_entries(&executed, &map), &map),
+            vec![(1, 2), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn test_statements_count_repeated_statement_once_per_run() {
+        // A loop of a single statement is indistinguishable from its `casm` instructions,
+        // so it is counted once, while every entry of its line is counted.
+        let map = statement_information_map(&[1, 2]);
+        let executed = vec![ids(&[0, 1, 0, 1, 0])];
+
+        assert_eq!(
+            line_counts(&build(&executed, &map), &map),
+            vec![(1, 3), (2, 2)]
+        );
+        assert_eq!(
+            line_counts(&build_line_entries(&executed, &map), &map),
+            vec![(1, 3), (2, 2)]
+        );
+
+        let executed = vec![ids(&[0, 0, 0])];
+        assert_eq!(line_counts(&build(&executed, &map), &map), vec![(1, 1)]);
+        assert_eq!(
+            line_counts(&build_line_entries(&executed, &map), &map),
+            vec![(1, 1)]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use crate::args::SourceLine;
+use crate::build::filter::statement_category_filter::{FilterChecks, StatementCategoryFilter};
+use crate::build::source_path_mapper::SourcePathMapper;
+use crate::build::statement_information::{LineRange, StatementInformation};
+use crate::build::{executed_statement_count, statement_information};
+use crate::loading::enriched_program::EnrichedProgram;
+use crate::loading::execution_data::ExecutionData;
+use cairo_annotations::annotations::coverage::{
+    CodeLocation, CoverageAnnotationsV1, LineNumber, SourceFileFullPath,
+    VersionedCoverageAnnotations,
+};
+use cairo_annotations::annotations::profiler::{
+    FunctionName, ProfilerAnnotationsV1, VersionedProfilerAnnotations,
+};
+use cairo_lang_sierra::program::StatementIdx;
+use cairo_lang_sierra_to_casm::compiler::CairoProgramDebugInfo;
+use camino::{Utf8Path, Utf8PathBuf};
+use std::collections::HashMap;
+
+/// How a single code location of a Sierra statement, mapped to the explained line, contributes to its coverage.
+pub struct StatementExplanation {
+    pub source_sierra_path: Utf8PathBuf,
+    pub idx: StatementIdx,
+    pub function_name: FunctionName,
+    pub source_file_full_path: SourceFileFullPath,
+    pub is_macro: bool,
+    /// Name of the invoked libfunc without the generic arguments, e.g. `store_temp`.
+    pub libfunc_name: Option<String>,
+    pub filter_checks: FilterChecks,
+    /// Whether this code location is the one the statement is reported at.
+    /// Only the first code location that passes all the checks is reported.
+    pub reported: bool,
+    /// Number of executed `casm` instructions mapped to the statement.
+    pub raw_execution_count: usize,
+    /// Execution count after deduplicating the neighboring statements.
+    pub execution_count: usize,
+    /// Number of line entries started by the statement.
+    pub line_entries: usize,
+}
+
+/// Build the [`StatementExplanation`]s of every statement of the program mapped to the given [`SourceLine`].
+pub fn build(
+    ExecutionData {
+        source_sierra_path,
+        call_executions,
+        enriched_program:
+            EnrichedProgram {
+                coverage_annotations,
+                profiler_annotations,
+                ..
+            },
+    }: ExecutionData,
+    casm_debug_info: &CairoProgramDebugInfo,
+    source_line: &SourceLine,
+    filter: &StatementCategoryFilter,
+    libfunc_names_by_idx: &HashMap<StatementIdx, String>,
+    path_mapper: &SourcePathMapper,
+) -> Vec<StatementExplanation> {
+    let locations = matching_locations(
+        &coverage_annotations,
+        &profiler_annotations,
+        source_line,
+        path_mapper,
+    );
+    let (statement_information_map, _) = statement_information::build_map(
+        coverage_annotations,
+        profiler_annotations,
+        filter,
+        path_mapper,
+    );
+    let executed_statement_ids =
+        executed_statement_count::build_ids(&call_executions, casm_debug_info);
+    let raw_execution_counts = executed_statement_count::build_raw(&executed_statement_ids);
+    let execution_counts =
+        executed_statement_count::build(&executed_statement_ids, &statement_information_map);
+    let line_entries = executed_statement_count::build_line_entries(
+        &executed_statement_ids,
+        &statement_information_map,
+    );
+
+    locations
+        .into_iter()
+        .map(
+            |MatchingLocation {
+                 idx,
+                 function_name,
+                 source_file_full_path,
+                 line_range,
+                 is_macro,
+             }| {
+                let filter_checks =
+                    filter.checks(idx, &function_name, &source_file_full_path, is_macro);
+                let source_file_full_path = path_mapper.to_output(source_file_full_path);
+                let reported =
+                    statement_information_map
+                        .get(&idx)
+                        .is_some_and(|statement_information| {
+                            *statement_information
+                                == StatementInformation {
+                                    idx,
+                                    function_name: function_name.clone(),
+                                    source_file_full_path: source_file_full_path.clone(),
+                                    line_range,
+                                }
+                        });
+
+                StatementExplanation {
+                    source_sierra_path: source_sierra_path.clone(),
+                    idx,
+                    function_name,
+                    source_file_full_path,
+                    is_macro,
+                    libfunc_name: libfunc_names_by_idx.get(&idx).cloned(),
+                    filter_checks,
+                    reported,
+                    raw_execution_count: raw_execution_counts
+                        .get(&idx)
+                        .copied()
+                        .unwrap_or_default(),
+                    execution_count: execution_counts.get(&idx).copied().unwrap_or_default(),
+                    line_entries: line_entries.get(&idx).copied().unwrap_or_default(),
+                }
+            },
+        )
+        .collect()
+}
+
+/// Check if any statement of the program is mapped to the given [`SourceLine`].
+/// This allows skipping the costly compilation of the programs that are not relevant.
+pub fn is_mapped(
+    EnrichedProgram {
+        coverage_annotations,
+        profiler_annotations,
+        ..
+    }: &EnrichedProgram,
+    source_line: &SourceLine,
+    path_mapper: &SourcePathMapper,
+) -> bool {
+    !matching_locations(
+        coverage_annotations,
+        profiler_annotations,
+        source_line,
+        path_mapper,
+    )
+    .is_empty()
+}
+
+/// Code location of a statement mapped to the explained line, with the remapped source file path.
+struct MatchingLocation {
+    idx: StatementIdx,
+    function_name: FunctionName,
+    source_file_full_path: SourceFileFullPath,
+    line_range: LineRange,
+    is_macro: bool,
+}
+
+/// Find the code locations of all statements mapped to the [`SourceLine`], sorted by the statement index.
+fn matching_locations(
+    VersionedCoverageAnnotations::V1(CoverageAnnotationsV1 {
+        statements_code_locations,
+    }): &VersionedCoverageAnnotations,
+    VersionedProfilerAnnotations::V1(ProfilerAnnotationsV1 {
+        statements_functions,
+    }): &VersionedProfilerAnnotations,
+    source_line: &SourceLine,
+    path_mapper: &SourcePathMapper,
+) -> Vec<MatchingLocation> {
+    let mut locations: Vec<_> = statements_code_locations
+        .iter()
+        .filter_map(|(idx, code_locations)| {
+            let function_names = statements_functions.get(idx)?;
+            Some(code_locations.iter().zip(function_names).map(
+                |(CodeLocation(source_file_full_path, line_range, is_macro), function_name)| {
+                    MatchingLocation {
+                        idx: *idx,
+                        function_name: function_name.clone(),
+                        source_file_full_path: path_mapper.remap(source_file_full_path.clone()),
+                        line_range: line_range.clone().into(),
+                        is_macro: is_macro.unwrap_or_default(),
+                    }
+                },
+            ))
+        })
+        .flatten()
+        .filter(|location| matches(location, source_line))
+        .collect();
+
+    locations.sort_by_key(|location| location.idx);
+    locations
+}
+
+/// Check if the location is mapped to the [`SourceLine`].
+/// Lines are matched the same way they are counted in the report.
+fn matches(
+    MatchingLocation {
+        source_file_full_path,
+        line_range,
+        ..
+    }: &MatchingLocation,
+    SourceLine { file, line }: &SourceLine,
+) -> bool {
+    Utf8Path::new(&source_file_full_path.0).ends_with(file)
+        && line_range
+            .into_iter()
+            .any(|line_number| line_number == LineNumber(*line))
+}
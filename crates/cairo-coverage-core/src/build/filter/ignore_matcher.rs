@@ -1,13 +1,13 @@
+use crate::setup::CAIRO_COVERAGE_IGNORE;
 use anyhow::{Error, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use ignore::Match;
 use ignore::gitignore::Gitignore;
 
-const CAIRO_COVERAGE_IGNORE: &str = ".cairo-coverage-ignore";
-
 /// Create a new instance of the [`CairoCoverageIgnoreMatcher`] that will be based on the [`CAIRO_COVERAGE_IGNORE`] file.
 pub fn build(path: &Utf8Path) -> Result<CairoCoverageIgnoreMatcher> {
     let ignore_matcher = find_ignore_file(path)
+        .inspect(|ignore_file| log::debug!("using ignore file {ignore_file}"))
         .map(Gitignore::new)
         .map(|(ignore, error)| {
             if let Some(error) = error {
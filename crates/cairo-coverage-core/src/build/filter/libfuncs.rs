@@ -1,15 +1,18 @@
+use crate::args::{LibfuncPolicy, LibfuncPreset};
 use cairo_lang_sierra::ids::ConcreteLibfuncId;
 use cairo_lang_sierra::program::{ConcreteLibfuncLongId, Program, Statement, StatementIdx};
 use std::collections::{HashMap, HashSet};
 use std::sync::LazyLock;
 
+/// Unreliable libfuncs of the [`LibfuncPreset::Default`] preset.
+///
 /// TODO(#235)
 /// This is not the best way to do this, and I'm not proud of it.
 /// However, it is definitely the easiest way to achieve this.
 /// Some functions like `store_temp` are used in many places.
 /// Removing it would eliminate a lot of true positives.
 /// However, users would likely be more frustrated by false negatives.
-pub static NOT_RELIABLE_LIBFUNCS: LazyLock<HashSet<String>> = LazyLock::new(|| {
+static NOT_RELIABLE_LIBFUNCS: LazyLock<HashSet<String>> = LazyLock::new(|| {
     [
         "drop",
         "enable_ap_tracking",
@@ -31,6 +34,68 @@ pub static NOT_RELIABLE_LIBFUNCS: LazyLock<HashSet<String>> = LazyLock::new(|| {
     .collect()
 });
 
+/// Matches the names of libfuncs that are unreliable according to the [`LibfuncPolicy`].
+pub struct UnreliableLibfuncMatcher {
+    preset: &'static HashSet<String>,
+    unreliable: Vec<String>,
+    reliable: Vec<String>,
+}
+
+/// Build a new instance of the [`UnreliableLibfuncMatcher`] from the [`LibfuncPolicy`].
+pub fn build_matcher(
+    LibfuncPolicy {
+        preset,
+        unreliable,
+        reliable,
+    }: &LibfuncPolicy,
+) -> UnreliableLibfuncMatcher {
+    static EMPTY: LazyLock<HashSet<String>> = LazyLock::new(HashSet::new);
+
+    UnreliableLibfuncMatcher {
+        preset: match preset {
+            LibfuncPreset::Default => &NOT_RELIABLE_LIBFUNCS,
+            LibfuncPreset::Strict => &EMPTY,
+        },
+        unreliable: unreliable.clone(),
+        reliable: reliable.clone(),
+    }
+}
+
+impl UnreliableLibfuncMatcher {
+    /// Check if the libfunc with the given name (without the generic arguments) is unreliable.
+    pub fn is_unreliable(&self, libfunc_name: &str) -> bool {
+        let matches_any = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| matches_pattern(pattern, libfunc_name))
+        };
+
+        (self.preset.contains(libfunc_name) || matches_any(&self.unreliable))
+            && !matches_any(&self.reliable)
+    }
+}
+
+/// Check if the name matches the pattern, where `*` matches any sequence of characters.
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = parts.next().and_then(|prefix| name.strip_prefix(prefix)) else {
+        return false;
+    };
+    let parts: Vec<_> = parts.collect();
+
+    let Some((suffix, middle)) = parts.split_last() else {
+        // No wildcards, the whole name must match.
+        return rest.is_empty();
+    };
+    for part in middle {
+        let Some(index) = rest.find(part) else {
+            return false;
+        };
+        rest = &rest[index + part.len()..];
+    }
+    rest.ends_with(suffix)
+}
+
 /// Build a map from statement index to the name of the statement.
 pub fn build_names_map(program: &Program) -> HashMap<StatementIdx, String> {
     let libfuncs_long_ids_by_ids = program
@@ -79,3 +144,52 @@ fn truncate_at_chars(input: String, delimiters: &[char]) -> String {
         None => input,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(preset: LibfuncPreset, unreliable: &[&str], reliable: &[&str]) -> LibfuncPolicy {
+        LibfuncPolicy {
+            preset,
+            unreliable: unreliable.iter().map(ToString::to_string).collect(),
+            reliable: reliable.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("store_temp", "store_temp"));
+        assert!(!matches_pattern("store_temp", "store_temp_x"));
+        assert!(matches_pattern("struct_*", "struct_deconstruct"));
+        assert!(matches_pattern("*_const", "contract_address_const"));
+        assert!(matches_pattern("u*_overflowing_*", "u128_overflowing_sub"));
+        assert!(!matches_pattern("u*_overflowing_*", "felt252_sub"));
+        assert!(matches_pattern("*", "drop"));
+    }
+
+    #[test]
+    fn test_policy_adds_and_removes_libfuncs() {
+        let matcher = build_matcher(&policy(
+            LibfuncPreset::Default,
+            &["felt252_is_zero"],
+            &["store_temp", "struct_*"],
+        ));
+
+        assert!(matcher.is_unreliable("drop"));
+        assert!(matcher.is_unreliable("felt252_is_zero"));
+        assert!(!matcher.is_unreliable("store_temp"));
+        assert!(!matcher.is_unreliable("struct_construct"));
+    }
+
+    #[test]
+    fn test_strict_preset_counts_every_statement() {
+        let matcher = build_matcher(&policy(LibfuncPreset::Strict, &[], &[]));
+
+        assert!(
+            NOT_RELIABLE_LIBFUNCS
+                .iter()
+                .all(|libfunc_name| !matcher.is_unreliable(libfunc_name))
+        );
+    }
+}
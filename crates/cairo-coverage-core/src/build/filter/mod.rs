@@ -1,3 +1,5 @@
 pub mod ignore_matcher;
-mod libfuncs;
+pub mod libfuncs;
+pub mod non_executable;
+pub mod project_membership;
 pub mod statement_category_filter;
@@ -0,0 +1,138 @@
+use cairo_annotations::annotations::coverage::{
+    CodeLocation, CoverageAnnotationsV1, VersionedCoverageAnnotations,
+};
+use cairo_lang_sierra::program::StatementIdx;
+use cairo_lang_sierra_to_casm::compiler::CairoProgramDebugInfo;
+use itertools::Itertools;
+use std::collections::HashSet;
+
+/// Statements mapped to more distinct source lines than this come from code shared by many places,
+/// e.g. heavily inlined helpers, and cannot be attributed to any of them.
+const MAX_SHARED_LINES: usize = 4;
+
+/// Statements that cannot be attributed to the executed code, detected from the compiled program
+/// instead of relying on their libfuncs.
+pub struct NonExecutableStatements {
+    /// Statements compiled to no `casm` instructions, so they never appear in the trace.
+    without_casm: HashSet<StatementIdx>,
+    /// Statements mapped to more than [`MAX_SHARED_LINES`] distinct source lines.
+    shared: HashSet<StatementIdx>,
+}
+
+/// Build the [`NonExecutableStatements`] from the offsets in the [`CairoProgramDebugInfo`]
+/// and the code locations in the [`VersionedCoverageAnnotations`].
+pub fn build(
+    casm_debug_info: &CairoProgramDebugInfo,
+    VersionedCoverageAnnotations::V1(CoverageAnnotationsV1 {
+        statements_code_locations,
+    }): &VersionedCoverageAnnotations,
+) -> NonExecutableStatements {
+    let without_casm = casm_debug_info
+        .sierra_statement_info
+        .iter()
+        .enumerate()
+        .filter(|(_, statement_debug_info)| {
+            statement_debug_info.start_offset == statement_debug_info.end_offset
+        })
+        .map(|(idx, _)| StatementIdx(idx))
+        .collect();
+
+    let shared = statements_code_locations
+        .iter()
+        .filter(|(_, code_locations)| {
+            code_locations
+                .iter()
+                .map(|CodeLocation(source_file_full_path, span, _)| {
+                    (source_file_full_path, span.start.line)
+                })
+                .unique()
+                .count()
+                > MAX_SHARED_LINES
+        })
+        .map(|(idx, _)| *idx)
+        .collect();
+
+    NonExecutableStatements {
+        without_casm,
+        shared,
+    }
+}
+
+impl NonExecutableStatements {
+    /// Check if the statement was compiled to at least one `casm` instruction.
+    pub fn has_casm(&self, idx: StatementIdx) -> bool {
+        !self.without_casm.contains(&idx)
+    }
+
+    /// Check if the statement is mapped to more than [`MAX_SHARED_LINES`] distinct source lines.
+    pub fn is_shared(&self, idx: StatementIdx) -> bool {
+        self.shared.contains(&idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_annotations::annotations::coverage::{
+        ColumnNumber, LineNumber, SourceCodeLocation, SourceCodeSpan, SourceFileFullPath,
+    };
+    use cairo_lang_sierra_to_casm::compiler::{
+        ReturnStatementDebugInfo, SierraStatementDebugInfo, StatementKindDebugInfo,
+    };
+    use std::collections::HashMap;
+
+    fn statement_debug_info(start_offset: usize, end_offset: usize) -> SierraStatementDebugInfo {
+        SierraStatementDebugInfo {
+            start_offset,
+            end_offset,
+            instruction_idx: 0,
+            additional_kind_info: StatementKindDebugInfo::Return(ReturnStatementDebugInfo {
+                ref_values: Vec::new(),
+            }),
+        }
+    }
+
+    fn code_locations(lines: impl IntoIterator<Item = usize>) -> Vec<CodeLocation> {
+        lines
+            .into_iter()
+            .map(|line| {
+                let location = SourceCodeLocation {
+                    line: LineNumber(line),
+                    col: ColumnNumber(0),
+                };
+                CodeLocation(
+                    SourceFileFullPath("/project/src/lib.cairo".to_string()),
+                    SourceCodeSpan {
+                        start: location.clone(),
+                        end: location,
+                    },
+                    None,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_non_executable_statements() {
+        let casm_debug_info = CairoProgramDebugInfo {
+            sierra_statement_info: vec![
+                statement_debug_info(0, 2),
+                statement_debug_info(2, 2),
+                statement_debug_info(2, 3),
+            ],
+        };
+        let coverage_annotations = VersionedCoverageAnnotations::V1(CoverageAnnotationsV1 {
+            statements_code_locations: HashMap::from([
+                (StatementIdx(0), code_locations([1, 1, 1, 1, 1, 1])),
+                (StatementIdx(2), code_locations(1..=MAX_SHARED_LINES + 1)),
+            ]),
+        });
+
+        let non_executable = build(&casm_debug_info, &coverage_annotations);
+
+        assert!(non_executable.has_casm(StatementIdx(0)));
+        assert!(!non_executable.has_casm(StatementIdx(1)));
+        assert!(!non_executable.is_shared(StatementIdx(0)));
+        assert!(non_executable.is_shared(StatementIdx(2)));
+    }
+}
@@ -0,0 +1,238 @@
+use cairo_annotations::annotations::coverage::SourceFileFullPath;
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+
+/// Create a new instance of the [`ProjectMembership`].
+/// - `project_path` and `workspace_member_paths` are the directories with the user code.
+/// - `dependency_paths` are the directories of dependencies, e.g. in Scarb's cache.
+///   They take precedence over the user directories they are nested in.
+pub fn build(
+    project_path: &Utf8Path,
+    workspace_member_paths: &[Utf8PathBuf],
+    dependency_paths: &[Utf8PathBuf],
+) -> ProjectMembership {
+    let user_roots = roots(std::iter::once(project_path).chain(as_paths(workspace_member_paths)));
+    let dependency_roots = roots(as_paths(dependency_paths));
+
+    ProjectMembership {
+        user_roots,
+        dependency_roots,
+    }
+}
+
+/// Decides whether a source file belongs to the user project.
+/// Paths are compared component-wise after normalization, so `/work/proj-old` does not belong to `/work/proj`,
+/// while symlinked and non-canonical paths (e.g. `/work/proj/src/../src/lib.cairo`) do.
+pub struct ProjectMembership {
+    user_roots: Vec<Utf8PathBuf>,
+    dependency_roots: Vec<Utf8PathBuf>,
+}
+
+impl ProjectMembership {
+    /// Check if the given source file belongs to the user project.
+    /// This accesses the filesystem, so results should be cached by the caller.
+    pub fn contains(&self, source_file_full_path: &SourceFileFullPath) -> bool {
+        // Code generated by macros in older Scarb versions has virtual file markings appended to the path.
+        let (path, _) = source_file_full_path.remove_virtual_file_markings();
+        let path = Utf8Path::new(path);
+
+        // Scarb's virtual files (e.g. generated by plugins) are not real paths.
+        if !path.is_absolute() {
+            return false;
+        }
+
+        let candidates = normalized_forms(path);
+        let user_depth = deepest_matching_root(&self.user_roots, &candidates);
+        let dependency_depth = deepest_matching_root(&self.dependency_roots, &candidates);
+
+        match (user_depth, dependency_depth) {
+            (Some(user_depth), Some(dependency_depth)) => user_depth > dependency_depth,
+            (user_depth, _) => user_depth.is_some(),
+        }
+    }
+}
+
+fn as_paths(paths: &[Utf8PathBuf]) -> impl Iterator<Item = &Utf8Path> {
+    paths.iter().map(Utf8PathBuf::as_path)
+}
+
+/// Collect all normalized forms of the given directories.
+fn roots<'a>(paths: impl Iterator<Item = &'a Utf8Path>) -> Vec<Utf8PathBuf> {
+    let mut roots: Vec<_> = paths.flat_map(normalized_forms).collect();
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+/// Return the lexically normalized absolute path and, if it exists, also its canonical form.
+/// Both are needed as the sources may no longer exist on disk (e.g. were compiled in a container)
+/// and symlinks can only be resolved for existing paths.
+pub fn normalized_forms(path: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let absolute = std::path::absolute(path)
+        .ok()
+        .and_then(|absolute| Utf8PathBuf::from_path_buf(absolute).ok())
+        .unwrap_or_else(|| path.to_path_buf());
+    let lexical = normalize_lexically(&absolute);
+    let canonical = lexical
+        .canonicalize_utf8()
+        .ok()
+        .filter(|canonical| *canonical != lexical);
+
+    std::iter::once(lexical).chain(canonical).collect()
+}
+
+/// Resolve `.` and `..` components without accessing the filesystem.
+fn normalize_lexically(path: &Utf8Path) -> Utf8PathBuf {
+    path.components()
+        .fold(Utf8PathBuf::new(), |mut normalized, component| {
+            match component {
+                Utf8Component::CurDir => {}
+                Utf8Component::ParentDir => {
+                    normalized.pop();
+                }
+                component => normalized.push(component),
+            }
+            normalized
+        })
+}
+
+/// Return the number of components of the deepest root any of the `candidates` is inside.
+fn deepest_matching_root(roots: &[Utf8PathBuf], candidates: &[Utf8PathBuf]) -> Option<usize> {
+    roots
+        .iter()
+        .filter(|root| {
+            candidates
+                .iter()
+                .any(|candidate| candidate.starts_with(root))
+        })
+        .map(|root| root.components().count())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use assert_fs::fixture::PathChild;
+    use cairo_coverage_test_utils::{CreateFile, Utf8PathBufConversion};
+
+    fn path(path: impl AsRef<str>) -> SourceFileFullPath {
+        SourceFileFullPath(path.as_ref().to_string())
+    }
+
+    #[test]
+    fn test_file_inside_project_belongs_to_it() {
+        let membership = build("/work/proj".into(), &[], &[]);
+
+        assert!(membership.contains(&path("/work/proj/src/lib.cairo")));
+    }
+
+    #[test]
+    fn test_sibling_with_common_prefix_does_not_belong_to_project() {
+        let membership = build("/work/proj".into(), &[], &[]);
+
+        assert!(!membership.contains(&path("/work/proj-old/src/lib.cairo")));
+    }
+
+    #[test]
+    fn test_project_path_in_the_middle_does_not_belong_to_project() {
+        let membership = build("/work/proj".into(), &[], &[]);
+
+        assert!(!membership.contains(&path("/cache/work/proj/src/lib.cairo")));
+    }
+
+    #[test]
+    fn test_non_canonical_paths_are_normalized() {
+        let membership = build("/work/./proj/".into(), &[], &[]);
+
+        assert!(membership.contains(&path("/work/proj/src/../src/lib.cairo")));
+        assert!(!membership.contains(&path("/work/proj/../other/src/lib.cairo")));
+    }
+
+    #[test]
+    fn test_relative_project_path() {
+        let current_dir = std::env::current_dir().unwrap().to_utf8_path_buf();
+        let membership = build(".".into(), &[], &[]);
+
+        assert!(membership.contains(&path(current_dir.join("src/lib.cairo"))));
+        assert!(!membership.contains(&path("/other/src/lib.cairo")));
+    }
+
+    #[test]
+    fn test_virtual_file_markings_are_ignored() {
+        let membership = build("/work/proj".into(), &[], &[]);
+
+        assert!(membership.contains(&path(
+            "/work/proj/src/lib.cairo[array_inline_macro][assert_macro]"
+        )));
+    }
+
+    #[test]
+    fn test_virtual_files_do_not_belong_to_project() {
+        let membership = build("/work/proj".into(), &[], &[]);
+
+        assert!(!membership.contains(&path("lib.cairo")));
+    }
+
+    #[test]
+    fn test_workspace_members_outside_project_belong_to_it() {
+        let membership = build("/work/proj".into(), &["/work/shared".into()], &[]);
+
+        assert!(membership.contains(&path("/work/shared/src/lib.cairo")));
+    }
+
+    #[test]
+    fn test_dependencies_inside_project_do_not_belong_to_it() {
+        let membership = build(
+            "/work/proj".into(),
+            &[],
+            &["/work/proj/.cache/scarb/registry/std/core".into()],
+        );
+
+        assert!(!membership.contains(&path(
+            "/work/proj/.cache/scarb/registry/std/core/src/lib.cairo"
+        )));
+        assert!(membership.contains(&path("/work/proj/src/lib.cairo")));
+    }
+
+    #[test]
+    fn test_workspace_member_nested_in_dependency_belongs_to_project() {
+        let membership = build(
+            "/work/proj".into(),
+            &["/work/deps/vendored/member".into()],
+            &["/work/deps".into()],
+        );
+
+        assert!(membership.contains(&path("/work/deps/vendored/member/src/lib.cairo")));
+        assert!(!membership.contains(&path("/work/deps/other/src/lib.cairo")));
+    }
+
+    #[test]
+    #[cfg(unix)] // Creating symlinks requires additional privileges on Windows.
+    fn test_symlinked_project_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let real_project = temp_dir.child("real_project");
+        let source_file = real_project.create_file("src/lib.cairo").to_utf8_path_buf();
+        let symlinked_project = temp_dir.child("symlinked_project").to_utf8_path_buf();
+        std::os::unix::fs::symlink(real_project.path(), &symlinked_project).unwrap();
+
+        let membership = build(&symlinked_project, &[], &[]);
+
+        assert!(membership.contains(&path(source_file)));
+        assert!(membership.contains(&path(symlinked_project.join("src/lib.cairo"))));
+    }
+
+    #[test]
+    #[cfg(unix)] // Creating symlinks requires additional privileges on Windows.
+    fn test_symlinked_source_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.child("project").to_utf8_path_buf();
+        let outside_file = temp_dir.create_file("outside/lib.cairo");
+        let linked_file = project.join("src").join("lib.cairo");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::os::unix::fs::symlink(outside_file.path(), &linked_file).unwrap();
+
+        let membership = build(&project, &[], &[]);
+
+        assert!(membership.contains(&path(&linked_file)));
+    }
+}
@@ -1,30 +1,41 @@
 use crate::args::IncludedComponent;
 use crate::build::filter::ignore_matcher::CairoCoverageIgnoreMatcher;
-use crate::build::filter::libfuncs;
-use crate::build::filter::libfuncs::NOT_RELIABLE_LIBFUNCS;
+use crate::build::filter::libfuncs::UnreliableLibfuncMatcher;
+use crate::build::filter::non_executable::NonExecutableStatements;
+use crate::build::filter::project_membership::ProjectMembership;
 use crate::loading::enriched_program::EnrichedProgram;
 use cairo_annotations::annotations::coverage::SourceFileFullPath;
 use cairo_annotations::annotations::profiler::FunctionName;
 use cairo_lang_sierra::program::StatementIdx;
-use camino::Utf8PathBuf;
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 
 /// Statement category filter that is used to filter out statements that should not be included in the coverage report.
-/// `included_components` and `ignore_matcher` are references to reduce the amount of data that needs to be copied.
+/// `included_components`, `project_membership`, `ignore_matcher`, `libfunc_matcher` and `libfunc_names_by_idx` are references to reduce the amount of data that needs to be copied.
 pub struct StatementCategoryFilter<'a> {
-    user_project_path: String,
+    project_membership: &'a ProjectMembership,
     included_components: &'a [IncludedComponent],
     test_functions: HashSet<FunctionName>,
     ignore_matcher: &'a CairoCoverageIgnoreMatcher,
-    libfunc_names_by_idx: HashMap<StatementIdx, String>,
+    libfunc_matcher: &'a UnreliableLibfuncMatcher,
+    libfunc_names_by_idx: &'a HashMap<StatementIdx, String>,
+    /// Set only if the non-executable statements are excluded.
+    non_executable_statements: Option<NonExecutableStatements>,
+    /// Cache of [`ProjectMembership::contains`] results as it accesses the filesystem.
+    user_files: RefCell<HashMap<SourceFileFullPath, bool>>,
 }
 
 /// Build a new instance of the [`StatementCategoryFilter`] based on the given parameters.
+/// `libfunc_names_by_idx` is the [`libfuncs::build_names_map`](crate::build::filter::libfuncs::build_names_map) of the program.
+/// The [`NonExecutableStatements`] are excluded only if provided.
 pub fn build<'a>(
-    user_project_path: &Utf8PathBuf,
+    project_membership: &'a ProjectMembership,
     included_components: &'a [IncludedComponent],
     ignore_matcher: &'a CairoCoverageIgnoreMatcher,
+    libfunc_matcher: &'a UnreliableLibfuncMatcher,
+    libfunc_names_by_idx: &'a HashMap<StatementIdx, String>,
     enriched_program: &EnrichedProgram,
+    non_executable_statements: Option<NonExecutableStatements>,
 ) -> StatementCategoryFilter<'a> {
     let test_functions = enriched_program
         .test_executables
@@ -33,54 +44,185 @@ pub fn build<'a>(
         .map(FunctionName)
         .collect();
 
-    let user_project_path = user_project_path.to_string();
-    let libfunc_names_by_idx = libfuncs::build_names_map(&enriched_program.program);
-
     StatementCategoryFilter {
-        user_project_path,
+        project_membership,
         included_components,
         test_functions,
         ignore_matcher,
+        libfunc_matcher,
         libfunc_names_by_idx,
+        non_executable_statements,
+        user_files: RefCell::default(),
+    }
+}
+
+/// Reason for which a statement was excluded from the coverage report.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ExclusionReason {
+    /// The statement belongs to a test function and test functions are not included.
+    TestFunction,
+    /// The statement comes from a macro expansion and macros are not included.
+    Macro,
+    /// The source file is not a part of the project, e.g. it is a dependency or the core library.
+    OutsideProject,
+    /// The statement was compiled to no `casm` instructions, so it can never be executed.
+    NoCasm,
+    /// The statement is mapped to many source lines and cannot be attributed to any of them.
+    SharedLocation,
+    /// The libfunc of the statement is unreliable according to the [`LibfuncPolicy`](crate::args::LibfuncPolicy).
+    UnreliableLibfunc,
+    /// The source file is ignored by the `.cairo-coverage-ignore` file.
+    Ignored,
+}
+
+impl ExclusionReason {
+    /// Returns the name of the reason used in the reports.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            ExclusionReason::TestFunction => "test function",
+            ExclusionReason::Macro => "macro",
+            ExclusionReason::OutsideProject => "outside of the project",
+            ExclusionReason::NoCasm => "no casm instructions",
+            ExclusionReason::SharedLocation => "shared by many lines",
+            ExclusionReason::UnreliableLibfunc => "unreliable libfunc",
+            ExclusionReason::Ignored => "ignored",
+        }
     }
 }
 
+/// Results of the individual checks of the [`StatementCategoryFilter`] for a single code location of a statement,
+/// in the order they are evaluated. Each check is identified by the [`ExclusionReason`] reported when it fails.
+pub type FilterChecks = Vec<(ExclusionReason, bool)>;
+
+/// Code location of a statement checked by the [`StatementCategoryFilter`].
+struct CheckedLocation<'a> {
+    idx: StatementIdx,
+    function_name: &'a FunctionName,
+    source_file_full_path: &'a SourceFileFullPath,
+    is_macro: bool,
+}
+
+/// Check of the [`StatementCategoryFilter`], passing if the location should be included in the report.
+type Check = fn(&StatementCategoryFilter, &CheckedLocation) -> bool;
+
+/// Checks of the [`StatementCategoryFilter`] with the reason reported when they fail, in the order of precedence.
+const CHECKS: [(Check, ExclusionReason); 7] = [
+    (
+        |filter, location| filter.is_allowed_test_function(location.function_name),
+        ExclusionReason::TestFunction,
+    ),
+    (
+        |filter, location| filter.is_allowed_macro(location.function_name, location.is_macro),
+        ExclusionReason::Macro,
+    ),
+    (
+        |filter, location| filter.is_user_function(location.source_file_full_path),
+        ExclusionReason::OutsideProject,
+    ),
+    (
+        |filter, location| {
+            filter
+                .non_executable_statements
+                .as_ref()
+                .is_none_or(|statements| statements.has_casm(location.idx))
+        },
+        ExclusionReason::NoCasm,
+    ),
+    (
+        |filter, location| {
+            filter
+                .non_executable_statements
+                .as_ref()
+                .is_none_or(|statements| !statements.is_shared(location.idx))
+        },
+        ExclusionReason::SharedLocation,
+    ),
+    (
+        |filter, location| filter.is_reliable_libfunc(location.idx),
+        ExclusionReason::UnreliableLibfunc,
+    ),
+    (
+        |filter, location| filter.is_not_ignored(location.source_file_full_path),
+        ExclusionReason::Ignored,
+    ),
+];
+
 impl StatementCategoryFilter<'_> {
-    /// Check if statement with the given index should be included in the coverage report.
-    pub fn should_include(
+    /// Run every check of the filter for the statement, without stopping at the first failed one.
+    pub fn checks(
         &self,
         idx: StatementIdx,
         function_name: &FunctionName,
         source_file_full_path: &SourceFileFullPath,
         is_macro: bool,
-    ) -> bool {
-        self.is_allowed_macro(function_name, is_macro)
-            && self.is_user_function(source_file_full_path)
-            && self.is_reliable_libfunc(idx)
-            && self.is_not_ignored(source_file_full_path)
+    ) -> FilterChecks {
+        let location = CheckedLocation {
+            idx,
+            function_name,
+            source_file_full_path,
+            is_macro,
+        };
+        CHECKS
+            .iter()
+            .map(|(check, reason)| (*reason, check(self, &location)))
+            .collect()
     }
 
-    fn is_allowed_macro(&self, function_name: &FunctionName, is_macro: bool) -> bool {
-        if self.test_functions.contains(function_name) {
-            self.included_components
+    /// Check if statement with the given index should be excluded from the coverage report and return the reason
+    /// of the first failed check. Returns `None` if the statement should be included.
+    pub fn exclusion_reason(
+        &self,
+        idx: StatementIdx,
+        function_name: &FunctionName,
+        source_file_full_path: &SourceFileFullPath,
+        is_macro: bool,
+    ) -> Option<ExclusionReason> {
+        let location = CheckedLocation {
+            idx,
+            function_name,
+            source_file_full_path,
+            is_macro,
+        };
+        CHECKS
+            .iter()
+            .find(|(check, _)| !check(self, &location))
+            .map(|(_, reason)| *reason)
+    }
+
+    fn is_allowed_test_function(&self, function_name: &FunctionName) -> bool {
+        !self.test_functions.contains(function_name)
+            || self
+                .included_components
                 .contains(&IncludedComponent::TestFunctions)
-        } else if is_macro {
-            self.included_components
-                .contains(&IncludedComponent::Macros)
-        } else {
-            true
-        }
     }
 
+    /// Test functions are checked separately, so the ones coming from macros are allowed here.
+    fn is_allowed_macro(&self, function_name: &FunctionName, is_macro: bool) -> bool {
+        !is_macro
+            || self.test_functions.contains(function_name)
+            || self
+                .included_components
+                .contains(&IncludedComponent::Macros)
+    }
     fn is_user_function(&self, source_file_full_path: &SourceFileFullPath) -> bool {
-        source_file_full_path.0.contains(&self.user_project_path)
+        if let Some(&is_user_file) = self.user_files.borrow().get(source_file_full_path) {
+            return is_user_file;
+        }
+
+        let is_user_file = self.project_membership.contains(source_file_full_path);
+        self.user_files
+            .borrow_mut()
+            .insert(source_file_full_path.clone(), is_user_file);
+        is_user_file
     }
 
     fn is_reliable_libfunc(&self, idx: StatementIdx) -> bool {
         !self
             .libfunc_names_by_idx
             .get(&idx)
-            .is_some_and(|libfunc_name| NOT_RELIABLE_LIBFUNCS.contains(libfunc_name))
+            .is_some_and(|libfunc_name| self.libfunc_matcher.is_unreliable(libfunc_name))
     }
 
     fn is_not_ignored(&self, source_file_full_path: &SourceFileFullPath) -> bool {
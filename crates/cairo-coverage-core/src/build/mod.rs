@@ -7,5 +7,8 @@
 //!   avoid wrapping or redefining external types unnecessarily.
 pub mod coverage_input;
 mod executed_statement_count;
+pub mod explanation;
 pub mod filter;
+pub mod source_path_mapper;
+pub mod stale_inputs;
 pub mod statement_information;
@@ -0,0 +1,157 @@
+use crate::args::PathPrefixMap;
+use crate::build::filter::project_membership::normalized_forms;
+use cairo_annotations::annotations::coverage::SourceFileFullPath;
+use camino::{Utf8Path, Utf8PathBuf};
+
+/// Maps [`SourceFileFullPath`] baked into the programs at compile time to the paths used in the report.
+pub struct SourcePathMapper<'a> {
+    path_prefix_maps: &'a [PathPrefixMap],
+    /// Normalized forms of the project path the paths are reported relative to, empty if they are reported as is.
+    relative_to: Vec<Utf8PathBuf>,
+}
+
+/// Build a new instance of the [`SourcePathMapper`].
+/// If `relative_paths` is set, paths inside the `project_path` are reported relative to it.
+/// The `project_path` is normalized once, so relative and non-canonical paths (e.g. `.`) are handled too.
+pub fn build<'a>(
+    path_prefix_maps: &'a [PathPrefixMap],
+    relative_paths: bool,
+    project_path: &Utf8Path,
+) -> SourcePathMapper<'a> {
+    SourcePathMapper {
+        path_prefix_maps,
+        relative_to: if relative_paths {
+            normalized_forms(project_path)
+        } else {
+            Vec::new()
+        },
+    }
+}
+
+impl SourcePathMapper<'_> {
+    /// Apply the first matching [`PathPrefixMap`] to the path.
+    /// The remapped path is what all filters operate on.
+    pub fn remap(&self, source_file_full_path: SourceFileFullPath) -> SourceFileFullPath {
+        let path = Utf8Path::new(&source_file_full_path.0);
+        self.path_prefix_maps
+            .iter()
+            .find_map(|PathPrefixMap { from, to }| {
+                let suffix = path.strip_prefix(from).ok()?;
+                Some(SourceFileFullPath(join(to, suffix).to_string()))
+            })
+            .unwrap_or(source_file_full_path)
+    }
+
+    /// Convert an already remapped path to the form in which it is written to the report.
+    pub fn to_output(&self, source_file_full_path: SourceFileFullPath) -> SourceFileFullPath {
+        self.relative_to
+            .iter()
+            .find_map(|project_path| {
+                let relative = Utf8Path::new(&source_file_full_path.0)
+                    .strip_prefix(project_path)
+                    .ok()?;
+                Some(SourceFileFullPath(relative.to_string()))
+            })
+            .unwrap_or(source_file_full_path)
+    }
+}
+
+/// Join `suffix` to `prefix` without adding a trailing separator when the `suffix` is empty.
+fn join(prefix: &Utf8Path, suffix: &Utf8Path) -> Utf8PathBuf {
+    if suffix.as_str().is_empty() {
+        prefix.to_path_buf()
+    } else {
+        prefix.join(suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use cairo_coverage_test_utils::Utf8PathBufConversion;
+
+    fn path(path: &str) -> SourceFileFullPath {
+        SourceFileFullPath(path.to_string())
+    }
+
+    fn prefix_map(from: &str, to: &str) -> PathPrefixMap {
+        PathPrefixMap {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+
+    #[test]
+    fn test_remap_replaces_matching_prefix() {
+        let maps = [prefix_map("/builds/xyz", "/home/user/project")];
+        let mapper = build(&maps, false, "/home/user/project".into());
+
+        assert_eq!(
+            mapper.remap(path("/builds/xyz/src/lib.cairo")),
+            path("/home/user/project/src/lib.cairo")
+        );
+    }
+
+    #[test]
+    fn test_remap_uses_first_matching_rule() {
+        let maps = [
+            prefix_map("/builds/xyz/src", "/first"),
+            prefix_map("/builds/xyz", "/second"),
+        ];
+        let mapper = build(&maps, false, "/".into());
+
+        assert_eq!(
+            mapper.remap(path("/builds/xyz/src/lib.cairo")),
+            path("/first/lib.cairo")
+        );
+    }
+
+    #[test]
+    fn test_remap_matches_whole_components_only() {
+        let maps = [prefix_map("/builds/xyz", "/home/user/project")];
+        let mapper = build(&maps, false, "/".into());
+
+        assert_eq!(
+            mapper.remap(path("/builds/xyz-old/src/lib.cairo")),
+            path("/builds/xyz-old/src/lib.cairo")
+        );
+    }
+
+    #[test]
+    fn test_to_output_strips_project_path() {
+        let mapper = build(&[], true, "/home/user/project".into());
+
+        assert_eq!(
+            mapper.to_output(path("/home/user/project/src/lib.cairo")),
+            path("src/lib.cairo")
+        );
+        assert_eq!(
+            mapper.to_output(path("/home/user/other/src/lib.cairo")),
+            path("/home/user/other/src/lib.cairo")
+        );
+    }
+
+    #[test]
+    fn test_to_output_strips_non_canonical_project_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.canonicalize().unwrap().to_utf8_path_buf();
+        let non_canonical = project_path.join("src").join(".").join("..");
+        let mapper = build(&[], true, &non_canonical);
+
+        assert_eq!(
+            mapper.to_output(path(project_path.join("src/lib.cairo").as_str())),
+            path("src/lib.cairo")
+        );
+    }
+
+    #[test]
+    fn test_to_output_keeps_absolute_paths_by_default() {
+        let mapper = build(&[], false, "/home/user/project".into());
+
+        assert_eq!(
+            mapper.to_output(path("/home/user/project/src/lib.cairo")),
+            path("/home/user/project/src/lib.cairo")
+        );
+    }
+}
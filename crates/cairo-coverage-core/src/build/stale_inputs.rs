@@ -0,0 +1,240 @@
+use crate::build::filter::project_membership::ProjectMembership;
+use crate::build::source_path_mapper::SourcePathMapper;
+use crate::loading::execution_data::ExecutionData;
+use cairo_annotations::annotations::coverage::{
+    CodeLocation, CoverageAnnotationsV1, SourceFileFullPath, VersionedCoverageAnnotations,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
+
+/// Input that changed after the program was built, so the coverage computed from it may be wrong.
+#[derive(Debug, Eq, PartialEq)]
+pub struct StaleInput {
+    pub path: Utf8PathBuf,
+    pub reason: String,
+    pub severity: Severity,
+}
+
+/// How a [`StaleInput`] is reported.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+    /// Fails the program in the `strict` mode, otherwise reported as a warning.
+    Error,
+    /// Always reported as a warning, as the input may still be up to date,
+    /// e.g. when the program was rebuilt without any changes.
+    Warning,
+}
+
+/// Source file as it is on the disk now.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct SourceFile {
+    line_count: usize,
+    modified: Option<SystemTime>,
+}
+
+/// Source files of the projects, read once and shared by all programs.
+#[derive(Default)]
+pub struct SourceFiles(Mutex<HashMap<Utf8PathBuf, Option<SourceFile>>>);
+
+impl SourceFiles {
+    /// Returns the [`SourceFile`] at the path, or `None` if it cannot be read.
+    fn get(&self, path: &Utf8Path) -> Option<SourceFile> {
+        if let Some(source_file) = self.lock().get(path) {
+            return *source_file;
+        }
+        // The file is read without holding the lock, so other programs are not blocked.
+        let source_file = fs::read_to_string(path).ok().map(|content| SourceFile {
+            line_count: content.lines().count(),
+            modified: modified(path),
+        });
+        self.lock().insert(path.to_path_buf(), source_file);
+        source_file
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Utf8PathBuf, Option<SourceFile>>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|_| unreachable!("no code panics while holding the lock"))
+    }
+}
+
+/// Build the list of [`StaleInput`]s of the program from the [`ExecutionData`]:
+/// - source files of the project that no longer exist, no longer contain the referenced lines
+///   or were modified after the program was built,
+/// - traces generated before the program was rebuilt. Programs are often rebuilt without any changes,
+///   e.g. by `scarb build` after `snforge test`, so these are only reported as a [`Severity::Warning`].
+///
+/// The programs do not record the contents of the source files they were built from, so changes are detected
+/// by comparing the line counts and the modification times with the ones of the program.
+/// Source files are remapped with the [`SourcePathMapper`] and only the ones in the project are checked.
+pub fn build(
+    execution_data: &ExecutionData,
+    project_membership: &ProjectMembership,
+    path_mapper: &SourcePathMapper,
+    source_files: &SourceFiles,
+) -> Vec<StaleInput> {
+    let program_path = &execution_data.source_sierra_path;
+    let Some(program_modified) = modified(program_path) else {
+        return Vec::new();
+    };
+
+    let stale_traces = execution_data
+        .call_executions
+        .iter()
+        .map(|call_execution| &call_execution.trace_path)
+        .unique()
+        .filter(|trace_path| {
+            modified(trace_path).is_some_and(|modified| modified < program_modified)
+        })
+        .map(|trace_path| StaleInput {
+            path: trace_path.clone(),
+            reason: "the trace was generated before the program was rebuilt".to_string(),
+            severity: Severity::Warning,
+        });
+
+    let stale_sources = last_referenced_lines(
+        &execution_data.enriched_program.coverage_annotations,
+        path_mapper,
+    )
+    .into_iter()
+    .filter(|(source_file_full_path, _)| project_membership.contains(source_file_full_path))
+    .filter_map(|(source_file_full_path, last_line)| {
+        let (path, _) = source_file_full_path.remove_virtual_file_markings();
+        let reason = stale_source_reason(
+            source_files.get(Utf8Path::new(path)),
+            last_line,
+            program_modified,
+        )?;
+        Some(StaleInput {
+            path: path.into(),
+            reason,
+            severity: Severity::Error,
+        })
+    });
+
+    stale_traces
+        .chain(stale_sources)
+        .sorted_by(|a, b| a.path.cmp(&b.path))
+        .collect()
+}
+
+/// Returns the last line (1-based) referenced in each remapped source file.
+fn last_referenced_lines(
+    VersionedCoverageAnnotations::V1(CoverageAnnotationsV1 {
+        statements_code_locations,
+    }): &VersionedCoverageAnnotations,
+    path_mapper: &SourcePathMapper,
+) -> HashMap<SourceFileFullPath, usize> {
+    statements_code_locations
+        .values()
+        .flatten()
+        .map(|CodeLocation(source_file_full_path, span, _)| {
+            // `SourceCodeSpan` is 0-based.
+            (
+                path_mapper.remap(source_file_full_path.clone()),
+                span.end.line.0 + 1,
+            )
+        })
+        .into_grouping_map()
+        .max()
+}
+
+/// Returns why the [`SourceFile`] is stale, or `None` if it still contains the last referenced line
+/// and was not modified after the program was built.
+fn stale_source_reason(
+    source_file: Option<SourceFile>,
+    last_line: usize,
+    program_modified: SystemTime,
+) -> Option<String> {
+    let Some(SourceFile {
+        line_count,
+        modified,
+    }) = source_file
+    else {
+        return Some("the source file no longer exists or cannot be read".to_string());
+    };
+    if line_count < last_line {
+        return Some(format!(
+            "the source file has {line_count} lines, while line {last_line} is referenced"
+        ));
+    }
+    modified
+        .is_some_and(|modified| modified > program_modified)
+        .then(|| "the source file was modified after the program was built".to_string())
+}
+
+fn modified(path: &Utf8Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use assert_fs::fixture::{FileWriteStr, PathChild};
+    use cairo_coverage_test_utils::Utf8PathBufConversion;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stale_source_reason() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.child("lib.cairo");
+        source.write_str("fn a() {}\nfn b() {}\n").unwrap();
+        let source_files = SourceFiles::default();
+        let source_file = source_files.get(&source.to_utf8_path_buf());
+        let built_later = SystemTime::now() + Duration::from_mins(1);
+
+        assert_eq!(
+            source_file.map(|source_file| source_file.line_count),
+            Some(2)
+        );
+        assert_eq!(stale_source_reason(source_file, 2, built_later), None);
+        assert_eq!(
+            stale_source_reason(source_file, 3, built_later).unwrap(),
+            "the source file has 2 lines, while line 3 is referenced"
+        );
+        // Edits that keep the referenced lines are detected by the modification time.
+        assert_eq!(
+            stale_source_reason(source_file, 2, SystemTime::UNIX_EPOCH).unwrap(),
+            "the source file was modified after the program was built"
+        );
+        assert_eq!(
+            stale_source_reason(
+                source_files.get(&temp_dir.child("missing.cairo").to_utf8_path_buf()),
+                1,
+                built_later
+            )
+            .unwrap(),
+            "the source file no longer exists or cannot be read"
+        );
+    }
+
+    #[test]
+    fn test_source_files_are_read_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.child("lib.cairo");
+        source.write_str("fn a() {}\n").unwrap();
+        let path = source.to_utf8_path_buf();
+        let source_files = SourceFiles::default();
+
+        assert_eq!(
+            source_files
+                .get(&path)
+                .map(|source_file| source_file.line_count),
+            Some(1)
+        );
+        source.write_str("fn a() {}\nfn b() {}\n").unwrap();
+        assert_eq!(
+            source_files
+                .get(&path)
+                .map(|source_file| source_file.line_count),
+            Some(1)
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use crate::build::filter::statement_category_filter::StatementCategoryFilter;
+use crate::build::filter::statement_category_filter::{ExclusionReason, StatementCategoryFilter};
+use crate::build::source_path_mapper::SourcePathMapper;
 use cairo_annotations::annotations::coverage::{
     CodeLocation, CoverageAnnotationsV1, LineNumber, SourceCodeSpan, SourceFileFullPath,
     VersionedCoverageAnnotations,
@@ -15,6 +16,16 @@ use std::ops::RangeInclusive;
 /// Mapping from Sierra statement IDs to additional information about the statement.
 pub type StatementInformationMap = HashMap<StatementIdx, StatementInformation>;
 
+/// Mapping from Sierra statement IDs to the statements excluded from the coverage report.
+pub type ExcludedStatementMap = HashMap<StatementIdx, ExcludedStatement>;
+
+/// Statement excluded from the coverage report, with the information of its most relevant code location.
+#[derive(Clone)]
+pub struct ExcludedStatement {
+    pub statement_information: StatementInformation,
+    pub reason: ExclusionReason,
+}
+
 /// Additional information about a statement that is needed for coverage analysis.
 #[derive(Clone, Eq, PartialEq)]
 pub struct StatementInformation {
@@ -52,7 +63,8 @@ impl IntoIterator for &LineRange {
     }
 }
 
-/// Builds [`StatementInformationMap`].
+/// Builds [`StatementInformationMap`] and [`ExcludedStatementMap`] of the statements rejected by the filter.
+/// Source file paths are remapped with the [`SourcePathMapper`] before filtering.
 pub fn build_map(
     VersionedCoverageAnnotations::V1(CoverageAnnotationsV1 {
         statements_code_locations,
@@ -61,37 +73,65 @@ pub fn build_map(
         mut statements_functions,
     }): VersionedProfilerAnnotations,
     filter: &StatementCategoryFilter,
-) -> StatementInformationMap {
-    statements_code_locations
-        .into_iter()
-        .filter_map(|(key, code_locations)| {
-            let function_names = statements_functions.remove(&key)?;
-            let statement_origin =
-                get_statement_information(key, code_locations, function_names, filter)?;
-            Some((key, statement_origin))
-        })
-        .collect()
+    path_mapper: &SourcePathMapper,
+) -> (StatementInformationMap, ExcludedStatementMap) {
+    let mut statement_information_map = StatementInformationMap::new();
+    let mut excluded_statement_map = ExcludedStatementMap::new();
+
+    for (key, code_locations) in statements_code_locations {
+        let Some(function_names) = statements_functions.remove(&key) else {
+            continue;
+        };
+        match get_statement_information(key, code_locations, function_names, filter, path_mapper) {
+            Some(Ok(statement_information)) => {
+                statement_information_map.insert(key, statement_information);
+            }
+            Some(Err(excluded_statement)) => {
+                excluded_statement_map.insert(key, excluded_statement);
+            }
+            None => {}
+        }
+    }
+
+    (statement_information_map, excluded_statement_map)
 }
 
 /// Get the statement information for a given statement ID.
 /// We take the first code location that matches the filter as they are sorted by priority.
+/// If none of them matches, the first code location is returned with the reason it was excluded.
+/// Returns `None` if the statement has no code locations.
 fn get_statement_information(
     idx: StatementIdx,
     code_locations: Vec<CodeLocation>,
     function_names: Vec<FunctionName>,
     filter: &StatementCategoryFilter,
-) -> Option<StatementInformation> {
-    code_locations.into_iter().zip(function_names).find_map(
-        |(CodeLocation(source_file_full_path, line_range, is_macro), function_name)| {
-            let is_macro = is_macro.unwrap_or_default();
-            filter
-                .should_include(idx, &function_name, &source_file_full_path, is_macro)
-                .then(|| StatementInformation {
-                    function_name,
-                    source_file_full_path,
-                    line_range: line_range.into(),
-                    idx,
-                })
-        },
-    )
+    path_mapper: &SourcePathMapper,
+) -> Option<Result<StatementInformation, ExcludedStatement>> {
+    let mut first_excluded = None;
+
+    for (CodeLocation(source_file_full_path, line_range, is_macro), function_name) in
+        code_locations.into_iter().zip(function_names)
+    {
+        let is_macro = is_macro.unwrap_or_default();
+        let source_file_full_path = path_mapper.remap(source_file_full_path);
+        let reason = filter.exclusion_reason(idx, &function_name, &source_file_full_path, is_macro);
+        let statement_information = StatementInformation {
+            function_name,
+            source_file_full_path: path_mapper.to_output(source_file_full_path),
+            line_range: line_range.into(),
+            idx,
+        };
+
+        match reason {
+            None => return Some(Ok(statement_information)),
+            Some(reason) => {
+                first_excluded.get_or_insert(ExcludedStatement {
+                    statement_information,
+                    reason,
+                });
+            }
+        }
+    }
+
+    first_excluded.map(Err)
 }
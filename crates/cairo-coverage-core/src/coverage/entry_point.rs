@@ -0,0 +1,64 @@
+use crate::hashmap_utils::merge::Merge;
+use crate::loading::entry_points::EntryPoint;
+use crate::loading::execution_data::ExecutionData;
+
+/// Entry points of a single contract together with the number of times they were invoked.
+/// Empty if the program is not a contract.
+#[derive(Clone, Default)]
+pub struct ContractEntryPoints {
+    pub entry_points: Vec<EntryPointInvocations>,
+}
+
+/// An [`EntryPoint`] and the number of times it was invoked.
+#[derive(Clone)]
+pub struct EntryPointInvocations {
+    pub entry_point: EntryPoint,
+    pub invocations: usize,
+}
+
+/// Creates [`ContractEntryPoints`] from the given [`ExecutionData`].
+pub fn create(execution_data: &ExecutionData) -> ContractEntryPoints {
+    let invoked_entry_points = execution_data
+        .call_executions
+        .iter()
+        .filter_map(|call_execution| call_execution.entry_point.as_ref())
+        .map(|entry_point| {
+            (
+                &entry_point.entry_point_type,
+                entry_point.entry_point_selector.0.to_biguint(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let entry_points = execution_data
+        .enriched_program
+        .entry_points
+        .iter()
+        .map(|entry_point| EntryPointInvocations {
+            invocations: invoked_entry_points
+                .iter()
+                .filter(|(kind, selector)| {
+                    **kind == entry_point.kind && *selector == entry_point.selector
+                })
+                .count(),
+            entry_point: entry_point.clone(),
+        })
+        .collect();
+
+    ContractEntryPoints { entry_points }
+}
+
+impl Merge for ContractEntryPoints {
+    /// Merges the invocations of the same entry points, e.g. of a contract executed through different traces.
+    fn merge(&mut self, other: Self) {
+        for entry_point in other.entry_points {
+            match self.entry_points.iter_mut().find(|existing| {
+                existing.entry_point.kind == entry_point.entry_point.kind
+                    && existing.entry_point.selector == entry_point.entry_point.selector
+            }) {
+                Some(existing) => existing.invocations += entry_point.invocations,
+                None => self.entry_points.push(entry_point),
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::loading::call_interactions::{CallInteractions, CallTarget, NestedCall};
+use cairo_annotations::trace_data::CallType;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Syscalls and nested calls performed by the tests, aggregated per contract and function.
+#[derive(Clone, Default)]
+pub struct Interactions {
+    /// Sorted by the caller and the syscall name.
+    pub syscalls: Vec<SyscallUsage>,
+    /// Sorted by the caller, the kind of the call and the callee.
+    pub calls: Vec<CallUsage>,
+}
+
+/// Syscall performed directly by a function of a contract.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SyscallUsage {
+    pub caller: CallTarget,
+    /// Name of the syscall, e.g. `EmitEvent`.
+    pub syscall: String,
+    pub call_count: usize,
+    /// Names of the tests that performed the syscall.
+    pub tests: BTreeSet<String>,
+}
+
+/// Call to another contract or a library call made directly by a function of a contract.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct CallUsage {
+    pub caller: CallTarget,
+    pub kind: CallKind,
+    pub callee: CallTarget,
+    pub call_count: usize,
+    /// Names of the tests that performed the call.
+    pub tests: BTreeSet<String>,
+}
+
+/// Kind of the call made to another contract.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum CallKind {
+    /// Call to another contract (`call_contract` syscall).
+    Call,
+    /// Call executing the code of another class in the context of the caller (`library_call` syscall).
+    LibraryCall,
+}
+
+impl CallKind {
+    /// Returns the name of the kind used in the reports.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            CallKind::Call => "call",
+            CallKind::LibraryCall => "library_call",
+        }
+    }
+}
+
+/// Aggregates the [`CallInteractions`] of all calls per contract and function.
+pub fn create(call_interactions: Vec<CallInteractions>) -> Interactions {
+    let mut syscalls: BTreeMap<(CallTarget, String), (usize, BTreeSet<String>)> = BTreeMap::new();
+    let mut calls: BTreeMap<(CallTarget, CallKind, CallTarget), (usize, BTreeSet<String>)> =
+        BTreeMap::new();
+
+    for CallInteractions {
+        test_name,
+        caller,
+        syscalls: call_syscalls,
+        nested_calls,
+    } in call_interactions
+    {
+        for (syscall, call_count) in call_syscalls {
+            let (total, tests) = syscalls
+                .entry((caller.clone(), syscall.to_string()))
+                .or_default();
+            *total += call_count;
+            tests.insert(test_name.clone());
+        }
+
+        for NestedCall { call_type, target } in nested_calls {
+            let kind = match call_type {
+                CallType::Call => CallKind::Call,
+                CallType::Delegate => CallKind::LibraryCall,
+            };
+            let (total, tests) = calls.entry((caller.clone(), kind, target)).or_default();
+            *total += 1;
+            tests.insert(test_name.clone());
+        }
+    }
+
+    Interactions {
+        syscalls: syscalls
+            .into_iter()
+            .map(|((caller, syscall), (call_count, tests))| SyscallUsage {
+                caller,
+                syscall,
+                call_count,
+                tests,
+            })
+            .collect(),
+        calls: calls
+            .into_iter()
+            .map(|((caller, kind, callee), (call_count, tests))| CallUsage {
+                caller,
+                kind,
+                callee,
+                call_count,
+                tests,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_annotations::trace_data::DeprecatedSyscallSelector;
+    use std::collections::HashMap;
+
+    fn target(contract: &str, function: &str) -> CallTarget {
+        CallTarget {
+            contract: contract.to_string(),
+            function: function.to_string(),
+        }
+    }
+
+    fn call_interactions(test_name: &str) -> CallInteractions {
+        CallInteractions {
+            test_name: test_name.to_string(),
+            caller: target("Proxy", "forward"),
+            syscalls: HashMap::from([(DeprecatedSyscallSelector::EmitEvent, 2)]),
+            nested_calls: vec![NestedCall {
+                call_type: CallType::Delegate,
+                target: target("0x123", "handle"),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_interactions_are_aggregated_across_tests() {
+        let interactions = create(vec![
+            call_interactions("test_a"),
+            call_interactions("test_b"),
+        ]);
+
+        assert_eq!(
+            interactions.syscalls,
+            vec![SyscallUsage {
+                caller: target("Proxy", "forward"),
+                syscall: "EmitEvent".to_string(),
+                call_count: 4,
+                tests: BTreeSet::from(["test_a".to_string(), "test_b".to_string()]),
+            }]
+        );
+        assert_eq!(
+            interactions.calls,
+            vec![CallUsage {
+                caller: target("Proxy", "forward"),
+                kind: CallKind::LibraryCall,
+                callee: target("0x123", "handle"),
+                call_count: 2,
+                tests: BTreeSet::from(["test_a".to_string(), "test_b".to_string()]),
+            }]
+        );
+    }
+}
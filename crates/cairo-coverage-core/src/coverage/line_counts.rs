@@ -0,0 +1,44 @@
+use crate::coverage::function::ExecutionCount;
+
+/// Number of executable lines found in a piece of code and how many of them were hit.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LineCounts {
+    pub found: usize,
+    pub hit: usize,
+}
+
+impl LineCounts {
+    /// Creates [`LineCounts`] from the execution counts of each line.
+    pub fn from_execution_counts(
+        execution_counts: impl IntoIterator<Item = ExecutionCount>,
+    ) -> Self {
+        execution_counts
+            .into_iter()
+            .fold(Self::default(), |acc, execution_count| Self {
+                found: acc.found + 1,
+                hit: acc.hit + usize::from(execution_count > 0),
+            })
+    }
+
+    /// Returns the percentage of lines hit. Code without any lines is considered fully covered.
+    #[expect(clippy::cast_precision_loss)] // Line counts are far below the precision limit of `f64`.
+    #[must_use]
+    pub fn percentage(&self) -> f64 {
+        if self.found == 0 {
+            100.0
+        } else {
+            self.hit as f64 * 100.0 / self.found as f64
+        }
+    }
+}
+
+impl std::ops::Add for LineCounts {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            found: self.found + other.found,
+            hit: self.hit + other.hit,
+        }
+    }
+}
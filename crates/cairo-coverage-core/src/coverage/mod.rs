@@ -1,3 +1,8 @@
+pub mod entry_point;
 pub mod file;
 pub mod function;
+pub mod interactions;
+pub mod line_counts;
+pub mod program;
 pub mod project;
+pub mod statement;
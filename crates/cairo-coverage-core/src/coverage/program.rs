@@ -0,0 +1,115 @@
+use crate::coverage::entry_point::ContractEntryPoints;
+use crate::coverage::project::ProjectCoverage;
+use crate::hashmap_utils::merge::{Merge, merge};
+use crate::loading::execution_data::ExecutionData;
+use camino::Utf8Path;
+use itertools::Itertools;
+
+/// Coverage of a single program, i.e. a contract or a test executable.
+/// It keeps track of which code was executed through which program.
+#[derive(Clone)]
+pub struct ProgramCoverage {
+    /// Name of the contract or, if it is not known, of the artifact the program was loaded from.
+    pub name: String,
+    /// Name of the artifact the program was loaded from, see [`artifact_name`].
+    pub artifact_name: String,
+    pub project_coverage: ProjectCoverage,
+    pub entry_points: ContractEntryPoints,
+}
+
+impl Merge for ProgramCoverage {
+    fn merge(&mut self, other: Self) {
+        self.project_coverage.merge(other.project_coverage);
+        self.entry_points.merge(other.entry_points);
+    }
+}
+
+/// Returns the name of the program from the given [`ExecutionData`].
+/// The contract name from the call trace is preferred, the artifact name is used otherwise.
+pub fn name(execution_data: &ExecutionData) -> String {
+    execution_data
+        .call_executions
+        .iter()
+        .filter_map(|call_execution| call_execution.entry_point.as_ref())
+        .find_map(|entry_point| entry_point.contract_name.clone())
+        .unwrap_or_else(|| artifact_name(&execution_data.source_sierra_path))
+}
+
+/// Merges [`ProgramCoverage`]s loaded from the same artifact, e.g. executed through different traces,
+/// and sorts them by the name.
+/// Programs are not merged by the name, as contracts with the same name can be defined in different packages.
+pub fn merge_by_artifact(
+    programs: impl IntoIterator<Item = ProgramCoverage>,
+) -> Vec<ProgramCoverage> {
+    programs
+        .into_iter()
+        .into_grouping_map_by(|program| program.artifact_name.clone())
+        .reduce(|acc, _, program| merge(acc, program))
+        .into_values()
+        .sorted_by(|a, b| (&a.name, &a.artifact_name).cmp(&(&b.name, &b.artifact_name)))
+        .collect()
+}
+
+/// Derives the name of the artifact from its path, which is unique across the packages of the workspace,
+/// e.g. `target/dev/package_Contract.contract_class.json` -> `package_Contract`.
+pub fn artifact_name(source_sierra_path: &Utf8Path) -> String {
+    let file_name = source_sierra_path.file_name().unwrap_or_default();
+    let file_name = [".gz", ".zst", ".json"]
+        .into_iter()
+        .fold(file_name, |file_name, extension| {
+            file_name.strip_suffix(extension).unwrap_or(file_name)
+        });
+    [".contract_class", ".test.sierra", ".sierra"]
+        .into_iter()
+        .find_map(|extension| file_name.strip_suffix(extension))
+        .unwrap_or(file_name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(name: &str, artifact_name: &str) -> ProgramCoverage {
+        ProgramCoverage {
+            name: name.to_string(),
+            artifact_name: artifact_name.to_string(),
+            project_coverage: ProjectCoverage::default(),
+            entry_points: ContractEntryPoints::default(),
+        }
+    }
+
+    #[test]
+    fn test_merge_by_artifact_keeps_contracts_from_different_packages() {
+        let programs = merge_by_artifact([
+            program("Contract", "pkg_b_Contract"),
+            program("Contract", "pkg_a_Contract"),
+            program("Contract", "pkg_a_Contract"),
+        ]);
+
+        assert_eq!(
+            programs
+                .iter()
+                .map(|program| program.artifact_name.as_str())
+                .collect::<Vec<_>>(),
+            ["pkg_a_Contract", "pkg_b_Contract"]
+        );
+    }
+
+    #[test]
+    fn test_artifact_name() {
+        assert_eq!(
+            artifact_name("target/dev/pkg_Contract.contract_class.json".into()),
+            "pkg_Contract"
+        );
+        assert_eq!(
+            artifact_name("pkg_Contract.contract_class.json.gz".into()),
+            "pkg_Contract"
+        );
+        assert_eq!(
+            artifact_name("target/dev/pkg_unittest.test.sierra.json".into()),
+            "pkg_unittest"
+        );
+        assert_eq!(artifact_name("program.sierra.json.zst".into()), "program");
+    }
+}
@@ -3,6 +3,7 @@ use crate::build::statement_information::LineRange;
 use crate::coverage::file::FileCoverage;
 use crate::coverage::function::FunctionCoverage;
 use cairo_annotations::annotations::coverage::SourceFileFullPath;
+use itertools::Itertools;
 use std::collections::HashMap;
 
 /// Mapping of [`SourceFileFullPath`] to [`FileCoverage`].
@@ -10,6 +11,7 @@ use std::collections::HashMap;
 pub type ProjectCoverage = HashMap<SourceFileFullPath, FileCoverage>;
 
 /// Creates a [`ProjectCoverage`] from the given [`CoverageInput`].
+/// The hit count of a line is the sum of the line hit counts of its statements.
 pub fn create(coverage_input: CoverageInput) -> ProjectCoverage {
     coverage_input.statement_information_map.into_iter().fold(
         ProjectCoverage::new(),
@@ -21,7 +23,7 @@ pub fn create(coverage_input: CoverageInput) -> ProjectCoverage {
                 .or_default();
 
             let executed_statement_count = coverage_input
-                .executed_statement_count
+                .line_hit_count
                 .get(&id)
                 .copied()
                 .unwrap_or_default();
@@ -60,3 +62,46 @@ pub fn truncate_to_one(project_coverage: &mut ProjectCoverage) {
         }
     }
 }
+
+/// Counts the lines of the [`ProjectCoverage`] that were executed at least once.
+/// A line shared by many functions of the same file is counted once.
+pub fn covered_line_count(project_coverage: &ProjectCoverage) -> usize {
+    project_coverage
+        .values()
+        .map(|file_coverage| {
+            file_coverage
+                .values()
+                .flat_map(|function_coverage| function_coverage.iter())
+                .filter(|(_, execution_count)| **execution_count > 0)
+                .map(|(line, _)| line)
+                .unique()
+                .count()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_annotations::annotations::coverage::LineNumber;
+    use cairo_annotations::annotations::profiler::FunctionName;
+
+    #[test]
+    fn test_covered_line_count_counts_shared_lines_once() {
+        let function = |lines: &[(usize, usize)]| {
+            lines
+                .iter()
+                .map(|&(line, count)| (LineNumber(line), count))
+                .collect::<FunctionCoverage>()
+        };
+        let project_coverage = ProjectCoverage::from([(
+            SourceFileFullPath("src/lib.cairo".to_string()),
+            FileCoverage::from([
+                (FunctionName("a".to_string()), function(&[(1, 1), (2, 0)])),
+                (FunctionName("b".to_string()), function(&[(1, 2), (3, 1)])),
+            ]),
+        )]);
+
+        assert_eq!(covered_line_count(&project_coverage), 2);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::build::coverage_input::CoverageInput;
+use crate::build::statement_information::{ExcludedStatement, StatementInformation};
+use crate::model::{SierraCoverage, StatementCoverage, StatementLocation};
+use cairo_lang_sierra::program::StatementIdx;
+use camino::Utf8PathBuf;
+use std::collections::HashMap;
+
+/// Creates a [`SierraCoverage`] from the given [`CoverageInput`] and the name of the libfunc invoked by each statement.
+pub fn create(
+    source_sierra_path: Utf8PathBuf,
+    coverage_input: &CoverageInput,
+    libfunc_names_by_idx: &HashMap<StatementIdx, String>,
+) -> SierraCoverage {
+    let statements = (0..coverage_input.statement_count)
+        .map(StatementIdx)
+        .map(|idx| {
+            let (statement_information, exclusion_reason) =
+                match coverage_input.statement_information_map.get(&idx) {
+                    Some(statement_information) => (Some(statement_information), None),
+                    None => match coverage_input.excluded_statement_map.get(&idx) {
+                        Some(ExcludedStatement {
+                            statement_information,
+                            reason,
+                        }) => (Some(statement_information), Some(*reason)),
+                        None => (None, None),
+                    },
+                };
+
+            StatementCoverage {
+                idx: idx.0,
+                libfunc_name: libfunc_names_by_idx.get(&idx).cloned(),
+                execution_count: coverage_input
+                    .executed_statement_count
+                    .get(&idx)
+                    .copied()
+                    .unwrap_or_default(),
+                location: statement_information.map(location),
+                exclusion_reason,
+            }
+        })
+        .collect();
+
+    SierraCoverage {
+        source_sierra_path,
+        statements,
+    }
+}
+
+/// Truncates the execution count of each statement to 1, like [`crate::coverage::project::truncate_to_one`].
+pub fn truncate_to_one(sierra_coverage: &mut SierraCoverage) {
+    for statement in &mut sierra_coverage.statements {
+        statement.execution_count = statement.execution_count.min(1);
+    }
+}
+
+fn location(
+    StatementInformation {
+        function_name,
+        source_file_full_path,
+        line_range,
+        ..
+    }: &StatementInformation,
+) -> StatementLocation {
+    StatementLocation {
+        path: source_file_full_path.0.clone(),
+        function: function_name.0.clone(),
+        start_line: line_range.start.0,
+        end_line: line_range.end.0,
+    }
+}
@@ -0,0 +1,121 @@
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use std::io;
+
+/// Error returned by the public functions of `cairo_coverage_core`.
+/// The variants describe the common problems with the inputs, so they can be handled separately.
+/// New variants may be added in minor releases, so matches on it need a wildcard arm.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// A trace file or a program could not be read.
+    #[error("failed to read file at path: {path}")]
+    ReadFile {
+        path: Utf8PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// A trace file is not a valid trace, e.g. it is not a valid JSON.
+    #[error("failed to deserialize trace file at path: {path}: {reason}")]
+    InvalidTrace { path: Utf8PathBuf, reason: String },
+
+    /// A program is neither a valid Sierra program nor a contract class.
+    #[error("failed to deserialize program at path: {path}: {reason}")]
+    InvalidProgram { path: Utf8PathBuf, reason: String },
+
+    /// The Sierra file referenced by a trace file was not found at any of the attempted locations.
+    #[error(
+        "sierra file `{source_sierra_path}` referenced by trace file at path: {trace_path} does not exist, attempted locations:\n{}",
+        attempted_locations.iter().map(|location| format!("- {location}")).join("\n")
+    )]
+    MissingSierraFile {
+        /// Path of the Sierra file as it appears in the trace file.
+        source_sierra_path: Utf8PathBuf,
+        trace_path: Utf8PathBuf,
+        attempted_locations: Vec<Utf8PathBuf>,
+    },
+
+    /// The program passed together with a VM trace file does not exist.
+    #[error("program at path: {path} given for trace file at path: {trace_path} does not exist")]
+    MissingProgram {
+        path: Utf8PathBuf,
+        trace_path: Utf8PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// A program lacks the debug information required to compute the coverage,
+    /// usually because it was compiled without the entries from the `hint` in `Scarb.toml`.
+    #[error("{reason} in program at path: {path}\n{hint}")]
+    MissingDebugInfo {
+        path: Utf8PathBuf,
+        reason: String,
+        /// Recommended `Scarb.toml` entries enabling the debug information.
+        hint: &'static str,
+    },
+
+    /// A trace or a source file changed after the program was built, so the computed coverage may be wrong.
+    /// Only returned for source files in the `strict` mode, otherwise a warning is logged.
+    #[error("stale input at path: {path} used with program at path: {program}: {reason}")]
+    StaleInput {
+        path: Utf8PathBuf,
+        /// Path of the program the input is stale for.
+        program: Utf8PathBuf,
+        reason: String,
+    },
+
+    /// None of the traces executed any program, e.g. because no trace file was provided.
+    #[error("at least one trace file must be provided")]
+    NoTraces,
+
+    /// Any other failure, e.g. a program that cannot be compiled to CASM.
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl Error {
+    /// Returns the name of the kind of the error, e.g. `invalid_trace`, used in machine-readable output.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::ReadFile { .. } => "read_file",
+            Error::InvalidTrace { .. } => "invalid_trace",
+            Error::InvalidProgram { .. } => "invalid_program",
+            Error::MissingSierraFile { .. } => "missing_sierra_file",
+            Error::MissingProgram { .. } => "missing_program",
+            Error::MissingDebugInfo { .. } => "missing_debug_info",
+            Error::StaleInput { .. } => "stale_input",
+            Error::NoTraces => "no_traces",
+            Error::Other(_) => "other",
+        }
+    }
+
+    /// Returns the paths of the inputs the error is about.
+    #[must_use]
+    pub fn paths(&self) -> Vec<&Utf8Path> {
+        match self {
+            Error::ReadFile { path, .. }
+            | Error::InvalidTrace { path, .. }
+            | Error::InvalidProgram { path, .. }
+            | Error::MissingDebugInfo { path, .. } => vec![path],
+            Error::MissingSierraFile {
+                source_sierra_path,
+                trace_path,
+                ..
+            } => vec![trace_path, source_sierra_path],
+            Error::MissingProgram {
+                path, trace_path, ..
+            } => vec![trace_path, path],
+            Error::StaleInput { path, program, .. } => vec![path, program],
+            Error::NoTraces | Error::Other(_) => Vec::new(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for Error {
+    /// Recovers the [`Error`] if it was raised as an [`anyhow::Error`], wrapping other errors in [`Error::Other`].
+    fn from(error: anyhow::Error) -> Self {
+        error.downcast().unwrap_or_else(Error::Other)
+    }
+}
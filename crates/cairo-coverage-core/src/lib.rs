@@ -1,59 +1,502 @@
+//! Core logic of `cairo-coverage`.
+//!
+//! Use [`run`] to generate a report in one of the supported formats, or [`coverage`] to get the structured
+//! coverage data from the [`model`] module and render it with the formatters from the [`output`] module:
+//!
+//! ```no_run
+//! use cairo_coverage_core::args::RunOptions;
+//! use cairo_coverage_core::output::lcov;
+//!
+//! let mut coverage = cairo_coverage_core::coverage(
+//!     vec!["snfoundry_trace/test_name.json".into()],
+//!     ".".into(),
+//!     RunOptions::default(),
+//! )?;
+//! coverage.files.retain(|file| !file.path.contains("/tests/"));
+//! println!("{}", lcov::fmt_string(&coverage.files));
+//! # Ok::<(), cairo_coverage_core::error::Error>(())
+//! ```
 pub mod args;
 mod build;
 mod coverage;
+pub mod error;
 mod hashmap_utils;
 mod loading;
-mod output;
+pub mod model;
+pub mod output;
+pub mod progress;
+pub mod report;
+pub mod setup;
+mod skipped_inputs;
 
-use crate::args::RunOptions;
-use crate::build::coverage_input;
-use crate::build::filter::ignore_matcher;
-use crate::build::filter::statement_category_filter;
+use crate::args::{FilterOptions, IncludedComponent, ReportOptions, RunOptions, SourceLine};
+use crate::build::filter::ignore_matcher::{self, CairoCoverageIgnoreMatcher};
+use crate::build::filter::libfuncs::{self, UnreliableLibfuncMatcher};
+use crate::build::filter::non_executable;
+use crate::build::filter::project_membership::{self, ProjectMembership};
+use crate::build::filter::statement_category_filter::{self, StatementCategoryFilter};
+use crate::build::source_path_mapper::{self, SourcePathMapper};
+use crate::build::stale_inputs::{self, Severity, SourceFiles, StaleInput};
+use crate::build::{coverage_input, explanation, statement_information};
+use crate::coverage::interactions::Interactions;
+use crate::coverage::program::ProgramCoverage;
+use crate::coverage::project::ProjectCoverage;
+use crate::error::Error;
 use crate::hashmap_utils::merge::merge;
-use crate::loading::execution_data;
+use crate::loading::enriched_program::EnrichedProgram;
+use crate::loading::execution_data::ExecutionData;
+use crate::loading::{enriched_program, execution_data};
+use crate::model::{CallUsage, Coverage, SierraCoverage, SyscallUsage};
 use crate::output::lcov;
-use anyhow::{Context, Result};
-use camino::Utf8PathBuf;
+use crate::progress::{Progress, ProgressEvent, Stage};
+use crate::report::{ContractReport, InputKind, Report, SkippedInput};
+use crate::skipped_inputs::SkippedInputs;
+use anyhow::Context;
+use cairo_lang_sierra::program::StatementIdx;
+use cairo_lang_sierra_to_casm::compiler::CairoProgramDebugInfo;
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::collections::HashMap;
 
 /// Run the core logic of `cairo-coverage` with the provided trace files, project path and [`RunOptions`].
 /// This function generates a coverage report in the LCOV format.
+/// Other formats can be produced from the [`Coverage`] returned by [`coverage`] with the
+/// [`Reporter`](output::reporter::Reporter)s.
 /// # Errors
-/// Fails if it can't produce the coverage report with the error message explaining the reason.
-#[expect(clippy::needless_pass_by_value)] // In case if we ever needed to take ownership of the arguments.
+/// Fails with an [`Error`] explaining why it can't produce the coverage report.
+/// If `keep_going` is set, inputs that cannot be processed are skipped and listed in the [`Report`] instead.
 pub fn run(
+    trace_files: Vec<Utf8PathBuf>,
+    project_path: Utf8PathBuf,
+    options: RunOptions,
+) -> Result<Report, Error> {
+    let split_by_contract = options.report.split_by_contract;
+    let progress = Progress::new(options.progress_callback.clone());
+
+    let coverage = coverage(trace_files, project_path, options)?;
+
+    let (output, contract_reports) = progress.time(Stage::Formatting, || {
+        let output = lcov::fmt_string(&coverage.files);
+
+        let contract_reports = if split_by_contract {
+            coverage
+                .programs
+                .iter()
+                .map(|program| ContractReport {
+                    name: program.name.clone(),
+                    lcov: lcov::fmt_string(&program.files),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        (output, contract_reports)
+    });
+
+    Ok(Report {
+        output,
+        skipped_inputs: coverage.skipped_inputs,
+        contract_reports,
+    })
+}
+
+/// Compute the [`Coverage`] with the provided trace files, project path and [`RunOptions`].
+/// Unlike [`run`], it returns the structured coverage data, which can be inspected, filtered
+/// and rendered with the formatters from the [`output`] module.
+/// Options related to the format of the report (`report.split_by_contract`) are ignored.
+/// # Errors
+/// Fails with an [`Error`] explaining why it can't compute the coverage.
+/// If `keep_going` is set, inputs that cannot be processed are skipped and listed in the [`Coverage`] instead.
+#[expect(clippy::needless_pass_by_value)] // In case if we ever needed to take ownership of the arguments.
+pub fn coverage(
     trace_files: Vec<Utf8PathBuf>,
     project_path: Utf8PathBuf,
     RunOptions {
-        include,
-        no_truncation,
+        filter:
+            FilterOptions {
+                include,
+                libfunc_policy,
+                exclude_non_executable,
+            },
+        report: report_options,
+        keep_going,
+        strict,
+        sierra_root,
+        path_prefix_maps,
+        relative_paths,
+        workspace_member_paths,
+        dependency_paths,
+        vm_traces,
+        progress_callback,
     }: RunOptions,
-) -> Result<String> {
-    let ignore_matcher = ignore_matcher::build(&project_path)?;
+) -> Result<Coverage, Error> {
+    let progress = Progress::new(progress_callback);
 
-    let mut project_coverage = execution_data::load(&trace_files)?
+    let (ignore_matcher, execution_data, call_interactions, skipped_inputs) =
+        progress.time(Stage::Loading, || {
+            let ignore_matcher = ignore_matcher::build(&project_path)?;
+            let skipped_inputs = SkippedInputs::new(keep_going);
+            let (execution_data, call_interactions) = execution_data::load(
+                &trace_files,
+                &vm_traces,
+                sierra_root.as_deref(),
+                &skipped_inputs,
+            )?;
+            anyhow::Ok((
+                ignore_matcher,
+                execution_data,
+                call_interactions,
+                skipped_inputs,
+            ))
+        })?;
+    progress.report(ProgressEvent::TracesLoaded {
+        traces: trace_files.len() + vm_traces.len(),
+        programs: execution_data.len(),
+    });
+
+    let shared_inputs = SharedInputs {
+        include: &include,
+        exclude_non_executable,
+        strict,
+        project_membership: project_membership::build(
+            &project_path,
+            &workspace_member_paths,
+            &dependency_paths,
+        ),
+        path_mapper: source_path_mapper::build(&path_prefix_maps, relative_paths, &project_path),
+        ignore_matcher,
+        libfunc_matcher: libfuncs::build_matcher(&libfunc_policy),
+        source_files: SourceFiles::default(),
+    };
+    let Interactions { syscalls, calls } = coverage::interactions::create(call_interactions);
+
+    let programs = execution_data
         .into_par_iter()
         .map(|execution_data| {
-            let filter = statement_category_filter::build(
-                &project_path,
-                &include,
-                &ignore_matcher,
-                &execution_data.enriched_program,
-            );
+            let source_sierra_path = execution_data.source_sierra_path.clone();
+            let program_coverage =
+                program_coverage(execution_data, &shared_inputs, report_options, &progress);
+            skipped_inputs.skip_on_error(InputKind::Program, &source_sierra_path, program_coverage)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    progress.time(Stage::Merging, || {
+        merge_programs(
+            programs.into_iter().flatten().collect(),
+            syscalls,
+            calls,
+            skipped_inputs.into_sorted_vec(),
+            report_options.no_truncation,
+        )
+    })
+}
+
+/// Inputs shared by all programs processed in a single run.
+struct SharedInputs<'a> {
+    include: &'a [IncludedComponent],
+    exclude_non_executable: bool,
+    strict: bool,
+    project_membership: ProjectMembership,
+    path_mapper: SourcePathMapper<'a>,
+    ignore_matcher: CairoCoverageIgnoreMatcher,
+    libfunc_matcher: UnreliableLibfuncMatcher,
+    source_files: SourceFiles,
+}
+
+impl SharedInputs<'_> {
+    /// Report the [`StaleInput`]s of the program, i.e. traces and source files that changed after it was built.
+    /// In the `strict` mode the first [`Severity::Error`] fails the program with [`Error::StaleInput`],
+    /// otherwise each is logged as a warning.
+    fn check_stale_inputs(&self, execution_data: &ExecutionData) -> anyhow::Result<()> {
+        for StaleInput {
+            path,
+            reason,
+            severity,
+        } in stale_inputs::build(
+            execution_data,
+            &self.project_membership,
+            &self.path_mapper,
+            &self.source_files,
+        ) {
+            let error = Error::StaleInput {
+                path,
+                program: execution_data.source_sierra_path.clone(),
+                reason,
+            };
+            if self.strict && severity == Severity::Error {
+                return Err(error.into());
+            }
+            log::warn!("{error}");
+        }
+        Ok(())
+    }
 
-            coverage_input::build(execution_data, &filter)
+    /// Build the [`StatementCategoryFilter`] of the program compiled to the [`CairoProgramDebugInfo`].
+    fn filter<'a>(
+        &'a self,
+        libfunc_names_by_idx: &'a HashMap<StatementIdx, String>,
+        enriched_program: &EnrichedProgram,
+        casm_debug_info: &CairoProgramDebugInfo,
+    ) -> StatementCategoryFilter<'a> {
+        statement_category_filter::build(
+            &self.project_membership,
+            self.include,
+            &self.ignore_matcher,
+            &self.libfunc_matcher,
+            libfunc_names_by_idx,
+            enriched_program,
+            self.exclude_non_executable.then(|| {
+                non_executable::build(casm_debug_info, &enriched_program.coverage_annotations)
+            }),
+        )
+    }
+}
+
+/// Compute the [`ProgramCoverage`] of a single program and, if `sierra_statements` is set, its [`SierraCoverage`].
+/// The time spent in each [`Stage`] is reported through the [`Progress`].
+fn program_coverage(
+    execution_data: ExecutionData,
+    shared_inputs: &SharedInputs,
+    ReportOptions {
+        hit_count_mode,
+        sierra_statements,
+        ..
+    }: ReportOptions,
+    progress: &Progress,
+) -> anyhow::Result<(ProgramCoverage, Option<SierraCoverage>)> {
+    let source_sierra_path = execution_data.source_sierra_path.clone();
+    shared_inputs.check_stale_inputs(&execution_data)?;
+    let casm_debug_info = progress
+        .time(Stage::CasmCompilation, || {
+            coverage_input::compile(&execution_data.enriched_program.program)
         })
-        .map(coverage::project::create)
-        .collect::<Vec<_>>()
+        .context("failed to compile program to casm")?;
+    progress.report(ProgressEvent::ProgramCompiled {
+        path: source_sierra_path.clone(),
+    });
+
+    let name = coverage::program::name(&execution_data);
+    let artifact_name = coverage::program::artifact_name(&source_sierra_path);
+    let entry_points = coverage::entry_point::create(&execution_data);
+    let ExecutionData {
+        call_executions,
+        enriched_program,
+        ..
+    } = execution_data;
+    let statement_count = enriched_program.program.statements.len();
+    let libfunc_names_by_idx = libfuncs::build_names_map(&enriched_program.program);
+
+    let statement_maps = progress.time(Stage::Filtering, || {
+        let filter =
+            shared_inputs.filter(&libfunc_names_by_idx, &enriched_program, &casm_debug_info);
+        statement_information::build_map(
+            enriched_program.coverage_annotations,
+            enriched_program.profiler_annotations,
+            &filter,
+            &shared_inputs.path_mapper,
+        )
+    });
+
+    let (program_coverage, sierra_coverage) = progress.time(Stage::StatementMapping, || {
+        let coverage_input = coverage_input::build(
+            &source_sierra_path,
+            &call_executions,
+            statement_count,
+            statement_maps,
+            &casm_debug_info,
+            hit_count_mode,
+        );
+        // Statements are reported per artifact, as their indices differ between artifacts.
+        let sierra_coverage = sierra_statements.then(|| {
+            coverage::statement::create(
+                source_sierra_path.clone(),
+                &coverage_input,
+                &libfunc_names_by_idx,
+            )
+        });
+        let program_coverage = ProgramCoverage {
+            name,
+            artifact_name,
+            project_coverage: coverage::project::create(coverage_input),
+            entry_points,
+        };
+        (program_coverage, sierra_coverage)
+    });
+    log::debug!(
+        "{source_sierra_path}: {} covered line(s)",
+        coverage::project::covered_line_count(&program_coverage.project_coverage)
+    );
+
+    progress.report(ProgressEvent::ProgramProcessed {
+        path: source_sierra_path,
+    });
+    Ok((program_coverage, sierra_coverage))
+}
+
+/// Merges the coverage of all programs into the [`Coverage`] and truncates the hit counts, unless `no_truncation` is set.
+fn merge_programs(
+    programs: Vec<(ProgramCoverage, Option<SierraCoverage>)>,
+    syscalls: Vec<SyscallUsage>,
+    calls: Vec<CallUsage>,
+    skipped_inputs: Vec<SkippedInput>,
+    no_truncation: bool,
+) -> Result<Coverage, Error> {
+    let (programs, sierra_coverages): (Vec<_>, Vec<_>) = programs.into_iter().unzip();
+    let mut programs = coverage::program::merge_by_artifact(programs);
+    let mut sierra_coverages: Vec<_> = sierra_coverages
         .into_iter()
-        // Versioned programs and contract classes can represent the same piece of code,
-        // so we merge the file coverage after processing them to avoid duplicate entries.
-        .reduce(merge)
-        .context("at least one trace file must be provided")?;
+        .flatten()
+        .sorted_by(|a, b| a.source_sierra_path.cmp(&b.source_sierra_path))
+        .collect();
+
+    // Versioned programs and contract classes can represent the same piece of code,
+    // so we merge the file coverage after processing them to avoid duplicate entries.
+    let project_coverage = programs
+        .iter()
+        .map(|program| program.project_coverage.clone())
+        .reduce(merge);
+
+    let mut project_coverage = match project_coverage {
+        Some(project_coverage) => project_coverage,
+        // With `keep_going` every input could have been skipped, which is reported instead of failing.
+        None if !skipped_inputs.is_empty() => ProjectCoverage::default(),
+        None => return Err(Error::NoTraces),
+    };
+
+    if !programs.is_empty() && coverage::project::covered_line_count(&project_coverage) == 0 {
+        log::warn!(
+            "the traces produced no covered lines, they either do not execute the code of the project or all of it is excluded from the report"
+        );
+    }
 
     if !no_truncation {
         coverage::project::truncate_to_one(&mut project_coverage);
+        for program in &mut programs {
+            coverage::project::truncate_to_one(&mut program.project_coverage);
+        }
+        for sierra_coverage in &mut sierra_coverages {
+            coverage::statement::truncate_to_one(sierra_coverage);
+        }
     }
 
-    Ok(lcov::fmt_string(&project_coverage))
+    Ok(model::create(
+        &project_coverage,
+        &programs,
+        syscalls,
+        calls,
+        sierra_coverages,
+        skipped_inputs,
+    ))
+}
+
+/// Check that the program at the given path has all the debug information required to compute its coverage.
+/// # Errors
+/// Fails with an [`Error`] explaining why the program cannot be used, e.g. [`Error::MissingDebugInfo`].
+pub fn check_program(source_sierra_path: &Utf8Path) -> Result<(), Error> {
+    enriched_program::check(&source_sierra_path.to_path_buf())?;
+    Ok(())
+}
+
+/// Explain the coverage of a single [`SourceLine`] with the provided trace files, project path and [`RunOptions`].
+/// This function lists every Sierra statement mapped to the line across all programs, together with the results
+/// of each filter check and their execution counts. Options related to the format of the report are ignored.
+/// # Errors
+/// Fails with an [`Error`] explaining why it can't load the inputs.
+/// If `keep_going` is set, inputs that cannot be processed are skipped and listed in the [`Report`] instead.
+#[expect(clippy::needless_pass_by_value)] // In case if we ever needed to take ownership of the arguments.
+pub fn explain(
+    trace_files: Vec<Utf8PathBuf>,
+    project_path: Utf8PathBuf,
+    source_line: &SourceLine,
+    RunOptions {
+        filter:
+            FilterOptions {
+                include,
+                libfunc_policy,
+                exclude_non_executable,
+            },
+        keep_going,
+        strict,
+        sierra_root,
+        path_prefix_maps,
+        relative_paths,
+        workspace_member_paths,
+        dependency_paths,
+        vm_traces,
+        ..
+    }: RunOptions,
+) -> Result<Report, Error> {
+    let shared_inputs = SharedInputs {
+        include: &include,
+        exclude_non_executable,
+        strict,
+        project_membership: project_membership::build(
+            &project_path,
+            &workspace_member_paths,
+            &dependency_paths,
+        ),
+        path_mapper: source_path_mapper::build(&path_prefix_maps, relative_paths, &project_path),
+        ignore_matcher: ignore_matcher::build(&project_path)?,
+        libfunc_matcher: libfuncs::build_matcher(&libfunc_policy),
+        source_files: SourceFiles::default(),
+    };
+    let skipped_inputs = SkippedInputs::new(keep_going);
+
+    let (execution_data, _) = execution_data::load(
+        &trace_files,
+        &vm_traces,
+        sierra_root.as_deref(),
+        &skipped_inputs,
+    )?;
+
+    let explanations = execution_data
+        .into_par_iter()
+        .filter(|execution_data| {
+            explanation::is_mapped(
+                &execution_data.enriched_program,
+                source_line,
+                &shared_inputs.path_mapper,
+            )
+        })
+        .map(|execution_data| {
+            let source_sierra_path = execution_data.source_sierra_path.clone();
+            let explanations = shared_inputs
+                .check_stale_inputs(&execution_data)
+                .and_then(|()| {
+                    coverage_input::compile(&execution_data.enriched_program.program)
+                        .context("failed to compile program to casm")
+                })
+                .map(|casm_debug_info| {
+                    let libfunc_names_by_idx =
+                        libfuncs::build_names_map(&execution_data.enriched_program.program);
+                    let filter = shared_inputs.filter(
+                        &libfunc_names_by_idx,
+                        &execution_data.enriched_program,
+                        &casm_debug_info,
+                    );
+                    explanation::build(
+                        execution_data,
+                        &casm_debug_info,
+                        source_line,
+                        &filter,
+                        &libfunc_names_by_idx,
+                        &shared_inputs.path_mapper,
+                    )
+                });
+            skipped_inputs.skip_on_error(InputKind::Program, &source_sierra_path, explanations)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let explanations: Vec<_> = explanations
+        .into_iter()
+        .flatten()
+        .flatten()
+        .sorted_by(|a, b| a.source_sierra_path.cmp(&b.source_sierra_path))
+        .collect();
+
+    Ok(Report {
+        output: output::explanation::fmt_string(source_line, &explanations),
+        skipped_inputs: skipped_inputs.into_sorted_vec(),
+        contract_reports: Vec::new(),
+    })
 }
@@ -0,0 +1,230 @@
+use cairo_annotations::trace_data::{
+    CallEntryPoint, CallTraceNode, CallTraceV1, CallType, DeprecatedSyscallSelector,
+    ExecutionResources, VersionedCallTrace,
+};
+use camino::Utf8Path;
+use std::collections::HashMap;
+
+/// Syscalls and nested calls performed directly by a single call from the call trace.
+pub struct CallInteractions {
+    /// Name of the test that performed the call.
+    pub test_name: String,
+    pub caller: CallTarget,
+    /// Syscalls performed by the call itself, excluding the ones performed by the nested calls.
+    pub syscalls: HashMap<DeprecatedSyscallSelector, usize>,
+    pub nested_calls: Vec<NestedCall>,
+}
+
+/// Contract and function of a call, in a human-readable form.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CallTarget {
+    /// Contract name or, if it is not known, the class hash or the contract address.
+    pub contract: String,
+    /// Function name or, if it is not known, the entry point selector.
+    pub function: String,
+}
+
+/// Call made to another contract (or a library call) from within a call.
+pub struct NestedCall {
+    pub call_type: CallType,
+    pub target: CallTarget,
+}
+
+/// Load the [`CallInteractions`] of every call from the given call trace.
+/// The test name is taken from the root call of the trace, or from the trace file name if it is not known.
+pub fn load(
+    VersionedCallTrace::V1(call_trace): &VersionedCallTrace,
+    call_trace_path: &Utf8Path,
+) -> Vec<CallInteractions> {
+    fn load_recursively(
+        call_trace: &CallTraceV1,
+        test_name: &str,
+        acc: &mut Vec<CallInteractions>,
+    ) {
+        let nested_call_traces: Vec<_> = call_trace
+            .nested_calls
+            .iter()
+            .filter_map(|call_trace_node| match call_trace_node {
+                CallTraceNode::EntryPointCall(nested_call_trace) => {
+                    Some(nested_call_trace.as_ref())
+                }
+                CallTraceNode::DeployWithoutConstructor => None,
+            })
+            .collect();
+
+        acc.push(CallInteractions {
+            test_name: test_name.to_string(),
+            caller: call_target(&call_trace.entry_point),
+            syscalls: own_syscalls(call_trace, &nested_call_traces),
+            nested_calls: nested_call_traces
+                .iter()
+                .map(|nested_call_trace| NestedCall {
+                    call_type: nested_call_trace.entry_point.call_type,
+                    target: call_target(&nested_call_trace.entry_point),
+                })
+                .collect(),
+        });
+
+        for nested_call_trace in nested_call_traces {
+            load_recursively(nested_call_trace, test_name, acc);
+        }
+    }
+
+    let test_name = call_trace
+        .entry_point
+        .function_name
+        .clone()
+        .unwrap_or_else(|| test_name_from_path(call_trace_path));
+
+    let mut call_interactions = Vec::new();
+    load_recursively(call_trace, &test_name, &mut call_interactions);
+    call_interactions
+}
+
+/// The syscall counters in the trace are cumulative, so the counters of the nested calls are subtracted.
+fn own_syscalls(
+    call_trace: &CallTraceV1,
+    nested_call_traces: &[&CallTraceV1],
+) -> HashMap<DeprecatedSyscallSelector, usize> {
+    let mut syscalls = syscall_counts(&call_trace.cumulative_resources);
+    for nested_call_trace in nested_call_traces {
+        for (syscall, call_count) in syscall_counts(&nested_call_trace.cumulative_resources) {
+            if let Some(own_call_count) = syscalls.get_mut(&syscall) {
+                *own_call_count = own_call_count.saturating_sub(call_count);
+            }
+        }
+    }
+    syscalls.retain(|_, call_count| *call_count > 0);
+    syscalls
+}
+
+fn syscall_counts(
+    execution_resources: &ExecutionResources,
+) -> HashMap<DeprecatedSyscallSelector, usize> {
+    execution_resources
+        .syscall_counter
+        .iter()
+        .flatten()
+        .map(|(syscall, usage)| (*syscall, usage.call_count))
+        .collect()
+}
+
+fn call_target(entry_point: &CallEntryPoint) -> CallTarget {
+    let contract =
+        entry_point
+            .contract_name
+            .clone()
+            .unwrap_or_else(|| match &entry_point.class_hash {
+                Some(class_hash) => class_hash.0.to_hex_string(),
+                None => entry_point.contract_address.0.to_hex_string(),
+            });
+    let function = entry_point
+        .function_name
+        .clone()
+        .unwrap_or_else(|| entry_point.entry_point_selector.0.to_hex_string());
+
+    CallTarget { contract, function }
+}
+
+/// `snforge` names the trace files after the tests, e.g. `package::tests::test_name.json`.
+fn test_name_from_path(call_trace_path: &Utf8Path) -> String {
+    let file_name = call_trace_path.file_name().unwrap_or_default();
+    [".gz", ".zst", ".json"]
+        .into_iter()
+        .fold(file_name, |file_name, extension| {
+            file_name.strip_suffix(extension).unwrap_or(file_name)
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_annotations::trace_data::{L1Resources, SyscallUsage};
+
+    fn call_trace(
+        function_name: &str,
+        syscalls: &[(DeprecatedSyscallSelector, usize)],
+        nested_calls: Vec<CallTraceV1>,
+    ) -> CallTraceV1 {
+        let syscall_counter = syscalls
+            .iter()
+            .map(|(syscall, call_count)| {
+                (
+                    *syscall,
+                    SyscallUsage {
+                        call_count: *call_count,
+                        linear_factor: 0,
+                    },
+                )
+            })
+            .collect();
+
+        CallTraceV1 {
+            entry_point: CallEntryPoint {
+                contract_name: Some("Contract".to_string()),
+                function_name: Some(function_name.to_string()),
+                ..CallEntryPoint::default()
+            },
+            cumulative_resources: ExecutionResources {
+                syscall_counter: Some(syscall_counter),
+                ..ExecutionResources::default()
+            },
+            used_l1_resources: L1Resources::default(),
+            nested_calls: nested_calls
+                .into_iter()
+                .map(|nested_call| CallTraceNode::EntryPointCall(Box::new(nested_call)))
+                .collect(),
+            cairo_execution_info: None,
+        }
+    }
+
+    #[test]
+    fn test_nested_syscalls_are_not_counted_for_the_caller() {
+        let nested = call_trace(
+            "nested",
+            &[(DeprecatedSyscallSelector::StorageRead, 2)],
+            vec![],
+        );
+        let root = call_trace(
+            "test_name",
+            &[
+                (DeprecatedSyscallSelector::StorageRead, 3),
+                (DeprecatedSyscallSelector::CallContract, 1),
+            ],
+            vec![nested],
+        );
+
+        let call_interactions = load(&VersionedCallTrace::V1(root), "trace.json".into());
+
+        assert_eq!(call_interactions.len(), 2);
+        assert_eq!(
+            call_interactions[0].syscalls,
+            HashMap::from([
+                (DeprecatedSyscallSelector::StorageRead, 1),
+                (DeprecatedSyscallSelector::CallContract, 1),
+            ])
+        );
+        assert_eq!(
+            call_interactions[0].nested_calls[0].target.function,
+            "nested"
+        );
+        assert_eq!(
+            call_interactions[1].syscalls,
+            HashMap::from([(DeprecatedSyscallSelector::StorageRead, 2)])
+        );
+        assert!(
+            call_interactions
+                .iter()
+                .all(|call_interactions| call_interactions.test_name == "test_name")
+        );
+    }
+
+    #[test]
+    fn test_test_name_from_path() {
+        assert_eq!(
+            test_name_from_path("snfoundry_trace/pkg::tests::test_name.json.gz".into()),
+            "pkg::tests::test_name"
+        );
+    }
+}
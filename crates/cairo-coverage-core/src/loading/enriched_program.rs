@@ -1,6 +1,12 @@
-use crate::loading::read_and_deserialize;
-use anyhow::{Context, Result};
-use cairo_annotations::annotations::coverage::VersionedCoverageAnnotations;
+use crate::error::Error;
+use crate::loading::entry_points::EntryPoint;
+use crate::loading::{entry_points, read_and_deserialize};
+use crate::report::InputKind;
+use crate::setup::{self, REQUIRED_COMPILER_SETTINGS};
+use anyhow::Result;
+use cairo_annotations::annotations::coverage::{
+    CoverageAnnotationsV1, VersionedCoverageAnnotations,
+};
 use cairo_annotations::annotations::profiler::VersionedProfilerAnnotations;
 use cairo_annotations::annotations::{AnnotationsError, TryFromDebugInfo};
 use cairo_lang_sierra::debug_info::DebugInfo;
@@ -8,20 +14,22 @@ use cairo_lang_sierra::ids::FunctionId;
 use cairo_lang_sierra::program::{Program, ProgramArtifact, VersionedProgram};
 use cairo_lang_starknet_classes::contract_class::ContractClass;
 use camino::Utf8PathBuf;
-use indoc::indoc;
 use serde::Deserialize;
+use std::sync::LazyLock;
 
 /// Sierra [`Program`] with:
 /// - `test_executables` to know which functions are test functions
 /// - `coverage_annotations` for line mappings
 /// - `profiler_annotations` for function mappings
 /// - `program` itself for the future transformations to casm debug info
+/// - `entry_points` of the contract, empty if the program is not a contract
 #[derive(Clone)]
 pub struct EnrichedProgram {
     pub test_executables: Vec<FunctionId>,
     pub coverage_annotations: VersionedCoverageAnnotations,
     pub profiler_annotations: VersionedProfilerAnnotations,
     pub program: Program,
+    pub entry_points: Vec<EntryPoint>,
 }
 /// As sierra program can be in the form of raw sierra (normal cairo program) and contract class (starknet contracts).
 /// We need to be able to deserialize both. That's why we create [`SierraProgram`] enum.
@@ -34,28 +42,61 @@ enum SierraProgram {
 
 /// Load [`EnrichedProgram`] from a given path.
 pub fn load(source_sierra_path: &Utf8PathBuf) -> Result<EnrichedProgram> {
-    let sierra_program = read_and_deserialize(source_sierra_path)?;
-    let (program, debug_info) = extract(sierra_program)?;
-    let coverage_annotations = deserialize_annotations(&debug_info)?;
-    let profiler_annotations = deserialize_annotations(&debug_info)?;
+    let sierra_program = read_and_deserialize(source_sierra_path, InputKind::Program)?;
+    let (program, debug_info, entry_points) = extract(sierra_program)?;
+    let debug_info = debug_info.ok_or_else(|| Error::MissingDebugInfo {
+        path: source_sierra_path.clone(),
+        reason: "debug info not found".to_string(),
+        hint: &RECOMMENDED_CAIRO_PROFILE_TOML,
+    })?;
+    let coverage_annotations = deserialize_annotations(&debug_info, source_sierra_path)?;
+    let profiler_annotations = deserialize_annotations(&debug_info, source_sierra_path)?;
     let test_executables = extract_test_executables(debug_info);
     Ok(EnrichedProgram {
         test_executables,
         coverage_annotations,
         profiler_annotations,
         program,
+        entry_points,
     })
 }
 
-/// Extract [`Program`] and [`DebugInfo`] from [`SierraProgram`].
-fn extract(sierra_program: SierraProgram) -> Result<(Program, DebugInfo)> {
+impl EnrichedProgram {
+    /// Returns `true` if any statement of the program is mapped to a code location.
+    /// Programs compiled without `unstable-add-statements-code-locations-debug-info` have empty annotations,
+    /// so none of their lines can be reported.
+    pub fn has_coverage_annotations(&self) -> bool {
+        let VersionedCoverageAnnotations::V1(CoverageAnnotationsV1 {
+            statements_code_locations,
+        }) = &self.coverage_annotations;
+        !statements_code_locations.is_empty()
+    }
+}
+
+/// Check that the program at the given path can be loaded and has non-empty coverage annotations.
+pub fn check(source_sierra_path: &Utf8PathBuf) -> Result<()> {
+    if load(source_sierra_path)?.has_coverage_annotations() {
+        Ok(())
+    } else {
+        Err(Error::MissingDebugInfo {
+            path: source_sierra_path.clone(),
+            reason: "coverage annotations are empty".to_string(),
+            hint: &RECOMMENDED_CAIRO_PROFILE_TOML,
+        }
+        .into())
+    }
+}
+
+/// Extract [`Program`], [`DebugInfo`] (if present) and [`EntryPoint`]s from [`SierraProgram`].
+fn extract(sierra_program: SierraProgram) -> Result<(Program, Option<DebugInfo>, Vec<EntryPoint>)> {
     match sierra_program {
-        SierraProgram::VersionedProgram(program) => extract_versioned_program(program),
+        SierraProgram::VersionedProgram(program) => Ok(extract_versioned_program(program)),
         SierraProgram::ContractClass(contract_class) => extract_contract_class(contract_class),
     }
 }
 
 /// Extract [`Program`] and [`DebugInfo`] from [`VersionedProgram`].
+/// Raw Sierra programs have no entry points.
 fn extract_versioned_program(
     VersionedProgram::V1 {
         program: ProgramArtifact {
@@ -64,46 +105,89 @@ fn extract_versioned_program(
         },
         ..
     }: VersionedProgram,
-) -> Result<(Program, DebugInfo)> {
-    Ok((
-        program,
-        debug_info.context("debug info not found in program")?,
-    ))
+) -> (Program, Option<DebugInfo>, Vec<EntryPoint>) {
+    (program, debug_info, Vec::new())
 }
 
-/// Extract [`Program`] and [`DebugInfo`] from [`ContractClass`].
-fn extract_contract_class(contract_class: ContractClass) -> Result<(Program, DebugInfo)> {
+/// Extract [`Program`], [`DebugInfo`] and [`EntryPoint`]s from [`ContractClass`].
+fn extract_contract_class(
+    contract_class: ContractClass,
+) -> Result<(Program, Option<DebugInfo>, Vec<EntryPoint>)> {
     let program = contract_class.extract_sierra_program(false)?.program;
-    let debug_info = contract_class
-        .sierra_program_debug_info
-        .context("debug info not found in contract")?;
-    Ok((program, debug_info))
+    let debug_info = contract_class.sierra_program_debug_info;
+    let entry_points = debug_info
+        .as_ref()
+        .map(|debug_info| {
+            entry_points::load(
+                &contract_class.entry_points_by_type,
+                contract_class.abi,
+                &program,
+                debug_info,
+            )
+        })
+        .unwrap_or_default();
+    Ok((program, debug_info, entry_points))
 }
 
 /// Extract test executables from [`DebugInfo`].
+/// Test runners register the test functions in [`DebugInfo::executables`] under their own names:
+/// - `snforge_internal_test_executable` for `snforge`
+/// - `test` for `cairo-test`
+///
+/// Other executables, e.g. `executable_raw` used by `scarb execute`, are regular user code.
 fn extract_test_executables(mut debug_info: DebugInfo) -> Vec<FunctionId> {
-    const SNFORGE_TEST_EXECUTABLE: &str = "snforge_internal_test_executable";
+    const TEST_EXECUTABLES: [&str; 2] = ["snforge_internal_test_executable", "test"];
 
-    debug_info
-        .executables
-        .swap_remove(SNFORGE_TEST_EXECUTABLE)
-        .unwrap_or_default()
+    TEST_EXECUTABLES
+        .into_iter()
+        .filter_map(|name| debug_info.executables.swap_remove(name))
+        .flatten()
+        .collect()
 }
 
+/// Entries of `Scarb.toml` needed for the programs to contain all the debug info used by `cairo-coverage`.
+static RECOMMENDED_CAIRO_PROFILE_TOML: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "perhaps you are missing the following entries in Scarb.toml:\n\n{}",
+        setup::profile_toml("dev", &REQUIRED_COMPILER_SETTINGS)
+    )
+});
+
 /// Deserialize annotations from [`DebugInfo`] and provide a helpful error message.
 fn deserialize_annotations<T: TryFromDebugInfo<Error = AnnotationsError>>(
     debug_info: &DebugInfo,
+    source_sierra_path: &Utf8PathBuf,
 ) -> Result<T> {
-    const RECOMMENDED_CAIRO_PROFILE_TOML: &str = indoc! {
-        r#"
-        perhaps you are missing the following entries in Scarb.toml:
+    T::try_from_debug_info(debug_info).map_err(|error| {
+        Error::MissingDebugInfo {
+            path: source_sierra_path.clone(),
+            reason: error.to_string(),
+            hint: &RECOMMENDED_CAIRO_PROFILE_TOML,
+        }
+        .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        [profile.dev.cairo]
-        unstable-add-statements-functions-debug-info = true
-        unstable-add-statements-code-locations-debug-info = true
-        inlining-strategy = "avoid"
-        "#
-    };
+    #[test]
+    fn test_extract_test_executables_of_all_runners() {
+        let mut debug_info = DebugInfo::default();
+        for (name, id) in [
+            ("snforge_internal_test_executable", 0),
+            ("test", 1),
+            ("executable_raw", 2),
+        ] {
+            debug_info
+                .executables
+                .insert(name.to_string(), vec![FunctionId::new(id)]);
+        }
 
-    T::try_from_debug_info(debug_info).context(RECOMMENDED_CAIRO_PROFILE_TOML)
+        assert_eq!(
+            extract_test_executables(debug_info),
+            vec![FunctionId::new(0), FunctionId::new(1)]
+        );
+    }
 }
@@ -0,0 +1,197 @@
+use cairo_annotations::annotations::profiler::FunctionName;
+use cairo_annotations::trace_data::EntryPointType;
+use cairo_lang_sierra::debug_info::DebugInfo;
+use cairo_lang_sierra::ids::FunctionId;
+use cairo_lang_sierra::program::{GenericArg, Program, Statement};
+use cairo_lang_starknet_classes::abi::{Contract, Item};
+use cairo_lang_starknet_classes::contract_class::{ContractEntryPoint, ContractEntryPoints};
+use cairo_lang_starknet_classes::keccak::starknet_keccak;
+use num_bigint::BigUint;
+use std::collections::{HashMap, HashSet};
+
+/// Entry point of a Starknet contract.
+#[derive(Clone)]
+pub struct EntryPoint {
+    /// Whether it is a constructor, an external function or an L1 handler.
+    pub kind: EntryPointType,
+    pub selector: BigUint,
+    /// Name of the entry point from the ABI or the hex encoded selector if the ABI is not available.
+    pub name: String,
+    /// Name of the Sierra function generated for the entry point (the `__wrapper__` function).
+    pub function_name: Option<FunctionName>,
+    /// Names of the wrapper function and the user functions it calls, e.g. the function implementing the entry point.
+    pub implementing_functions: Vec<FunctionName>,
+}
+
+/// Load the [`EntryPoint`]s from the parts of a contract class.
+pub fn load(
+    entry_points_by_type: &ContractEntryPoints,
+    abi: Option<Contract>,
+    program: &Program,
+    debug_info: &DebugInfo,
+) -> Vec<EntryPoint> {
+    let names_by_selector = abi.map(load_abi_names).unwrap_or_default();
+
+    let ContractEntryPoints {
+        external,
+        l1_handler,
+        constructor,
+    } = entry_points_by_type;
+
+    [
+        (EntryPointType::Constructor, constructor),
+        (EntryPointType::External, external),
+        (EntryPointType::L1Handler, l1_handler),
+    ]
+    .into_iter()
+    .flat_map(|(entry_point_type, entry_points)| {
+        entry_points
+            .iter()
+            .map(move |entry_point| (entry_point_type.clone(), entry_point))
+    })
+    .map(
+        |(
+            entry_point_type,
+            ContractEntryPoint {
+                selector,
+                function_idx,
+            },
+        )| {
+            let function_name = |id: &FunctionId| {
+                debug_info
+                    .user_func_names
+                    .get(id)
+                    .map(|name| FunctionName(name.to_string()))
+            };
+            let wrapper = program.funcs.get(*function_idx);
+            EntryPoint {
+                kind: entry_point_type,
+                name: names_by_selector
+                    .get(selector)
+                    .cloned()
+                    .unwrap_or_else(|| format!("{selector:#x}")),
+                function_name: wrapper.and_then(|function| function_name(&function.id)),
+                implementing_functions: wrapper
+                    .map(|function| {
+                        std::iter::once(&function.id)
+                            .chain(&called_functions(program, *function_idx))
+                            .filter_map(function_name)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                selector: selector.clone(),
+            }
+        },
+    )
+    .collect()
+}
+
+/// Returns the ids of the user functions called by the function with the given index, in the order of the calls.
+/// The function's statements span from its entry point to the entry point of the function following it.
+fn called_functions(program: &Program, function_idx: usize) -> Vec<FunctionId> {
+    let start = program.funcs[function_idx].entry_point.0;
+    let end = program
+        .funcs
+        .iter()
+        .map(|function| function.entry_point.0)
+        .filter(|&entry_point| entry_point > start)
+        .min()
+        .unwrap_or(program.statements.len());
+
+    let called_functions_by_libfunc_ids = program
+        .libfunc_declarations
+        .iter()
+        .filter(|declaration| declaration.long_id.generic_id.0 == "function_call")
+        .filter_map(
+            |declaration| match declaration.long_id.generic_args.as_slice() {
+                [GenericArg::UserFunc(function_id)] => Some((&declaration.id, function_id)),
+                _ => None,
+            },
+        )
+        .collect::<HashMap<_, _>>();
+
+    let mut seen = HashSet::new();
+    program.statements[start..end]
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Invocation(invocation) => {
+                called_functions_by_libfunc_ids.get(&invocation.libfunc_id)
+            }
+            Statement::Return(_) => None,
+        })
+        .filter(|function_id| seen.insert(**function_id))
+        .map(|function_id| (*function_id).clone())
+        .collect()
+}
+
+/// Load the names of all callable functions from the ABI, keyed by their selectors.
+fn load_abi_names(abi: Contract) -> HashMap<BigUint, String> {
+    fn load_recursively(items: impl IntoIterator<Item = Item>, acc: &mut HashMap<BigUint, String>) {
+        for item in items {
+            let name = match item {
+                Item::Function(function) => function.name,
+                Item::Constructor(constructor) => constructor.name,
+                Item::L1Handler(l1_handler) => l1_handler.name,
+                Item::Interface(interface) => {
+                    load_recursively(interface.items, acc);
+                    continue;
+                }
+                Item::Event(_) | Item::Struct(_) | Item::Enum(_) | Item::Impl(_) => continue,
+            };
+            acc.insert(starknet_keccak(name.as_bytes()), name);
+        }
+    }
+
+    let mut names_by_selector = HashMap::new();
+    load_recursively(abi, &mut names_by_selector);
+    names_by_selector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_lang_sierra::ProgramParser;
+
+    fn names(program: &Program, function_idx: usize) -> Vec<String> {
+        called_functions(program, function_idx)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_called_functions_are_taken_from_the_wrapper_statements() {
+        // The implementing function lives in a renamed module, so it cannot be derived from the wrapper name.
+        let program = ProgramParser::new()
+            .parse(
+                "
+                libfunc call_deserialize = function_call<user@pkg::Serde::deserialize>;
+                libfunc call_impl = function_call<user@pkg::renamed::Impl::<felt252>::get>;
+                libfunc call_other = function_call<user@pkg::other>;
+
+                call_deserialize() -> ();
+                call_impl() -> ();
+                call_deserialize() -> ();
+                return();
+                call_other() -> ();
+                return();
+                return();
+
+                pkg::Contract::__wrapper__Impl__get@0() -> ();
+                pkg::renamed::Impl::<felt252>::get@4() -> ();
+                pkg::Serde::deserialize@6() -> ();
+                ",
+            )
+            .unwrap();
+
+        assert_eq!(
+            names(&program, 0),
+            [
+                "pkg::Serde::deserialize",
+                "pkg::renamed::Impl::<felt252>::get"
+            ]
+        );
+        assert_eq!(names(&program, 1), ["pkg::other"]);
+        assert!(names(&program, 2).is_empty());
+    }
+}
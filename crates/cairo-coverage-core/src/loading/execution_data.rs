@@ -1,37 +1,83 @@
+use crate::args::VmTrace;
+use crate::loading::call_interactions::CallInteractions;
 use crate::loading::enriched_program::EnrichedProgram;
-use crate::loading::{enriched_program, execution_infos};
+use crate::loading::execution_infos::{CallExecution, GroupedCallExecutions};
+use crate::loading::{enriched_program, execution_infos, vm_trace};
+use crate::report::InputKind;
+use crate::skipped_inputs::SkippedInputs;
 use anyhow::Result;
-use cairo_annotations::trace_data::CasmLevelInfo;
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use std::collections::HashMap;
 
 /// Struct with all the necessary data loaded from the traces.
 pub struct ExecutionData {
-    pub casm_level_infos: Vec<CasmLevelInfo>,
+    pub source_sierra_path: Utf8PathBuf,
+    pub call_executions: Vec<CallExecution>,
     pub enriched_program: EnrichedProgram,
 }
 
-/// Load the [`ExecutionData`] from the given call trace path.
-/// We do that in bulk for deserialization and execution optimizations
-pub fn load(call_trace_paths: &[Utf8PathBuf]) -> Result<Vec<ExecutionData>> {
-    let grouped_execution_infos = execution_infos::load_grouped(call_trace_paths)?;
-    create_from_execution_infos(grouped_execution_infos)
+/// Load the [`ExecutionData`] from the given call trace paths and Cairo VM traces.
+/// We do that in bulk for deserialization and execution optimizations.
+/// The [`CallInteractions`] of all calls are returned alongside, as they do not depend on the programs.
+///
+/// Traces and programs that cannot be loaded are recorded in `skipped_inputs`.
+pub fn load(
+    call_trace_paths: &[Utf8PathBuf],
+    vm_traces: &[VmTrace],
+    sierra_root: Option<&Utf8Path>,
+    skipped_inputs: &SkippedInputs,
+) -> Result<(Vec<ExecutionData>, Vec<CallInteractions>)> {
+    let (mut grouped_execution_infos, call_interactions) =
+        execution_infos::load_grouped(call_trace_paths, sierra_root, skipped_inputs)?;
+    for (source_sierra_path, call_executions) in vm_trace::load_grouped(vm_traces, skipped_inputs)?
+    {
+        grouped_execution_infos
+            .entry(source_sierra_path)
+            .or_default()
+            .extend(call_executions);
+    }
+    for (source_sierra_path, call_executions) in &grouped_execution_infos {
+        log::debug!(
+            "{source_sierra_path}: {} call execution(s) loaded from the traces",
+            call_executions.len()
+        );
+    }
+    let execution_data = create_from_execution_infos(grouped_execution_infos, skipped_inputs)?;
+    log::info!(
+        "loaded {} trace(s) executing {} program(s)",
+        call_trace_paths.len() + vm_traces.len(),
+        execution_data.len()
+    );
+    Ok((execution_data, call_interactions))
 }
 
 /// Create the [`ExecutionData`] from the grouped execution infos.
 fn create_from_execution_infos(
-    grouped_execution_infos: HashMap<Utf8PathBuf, Vec<CasmLevelInfo>>,
+    grouped_execution_infos: GroupedCallExecutions,
+    skipped_inputs: &SkippedInputs,
 ) -> Result<Vec<ExecutionData>> {
     grouped_execution_infos
         .into_par_iter()
-        .map(|(source_sierra_path, casm_level_infos)| {
-            let loaded_program = enriched_program::load(&source_sierra_path)?;
-            let execution_data = ExecutionData {
-                casm_level_infos,
-                enriched_program: loaded_program,
-            };
+        .map(|(source_sierra_path, call_executions)| {
+            let loaded_program = skipped_inputs.skip_on_error(
+                InputKind::Program,
+                &source_sierra_path,
+                enriched_program::load(&source_sierra_path),
+            )?;
+            if let Some(enriched_program) = &loaded_program
+                && !enriched_program.has_coverage_annotations()
+            {
+                log::warn!(
+                    "program {source_sierra_path} has no coverage annotations, none of its lines will be reported"
+                );
+            }
+            let execution_data = loaded_program.map(|enriched_program| ExecutionData {
+                source_sierra_path,
+                call_executions,
+                enriched_program,
+            });
             Ok(execution_data)
         })
+        .filter_map(Result::transpose)
         .collect()
 }
@@ -1,55 +1,124 @@
-use crate::loading::read_and_deserialize;
+use crate::loading::call_interactions::{self, CallInteractions};
+use crate::loading::{read_and_deserialize, sierra_path};
+use crate::report::InputKind;
+use crate::skipped_inputs::SkippedInputs;
 use anyhow::Result;
 use cairo_annotations::trace_data::{
-    CairoExecutionInfo, CallTraceNode, CallTraceV1, CasmLevelInfo, VersionedCallTrace,
+    CairoExecutionInfo, CallEntryPoint, CallTraceNode, CallTraceV1, CasmLevelInfo,
+    VersionedCallTrace,
 };
-use camino::Utf8PathBuf;
-use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use camino::{Utf8Path, Utf8PathBuf};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use std::collections::HashMap;
 
-/// Load the grouped [`CairoExecutionInfo`].
+/// [`CallExecution`]s grouped by `source_sierra_path`.
+pub type GroupedCallExecutions = HashMap<Utf8PathBuf, Vec<CallExecution>>;
+
+/// [`CairoExecutionInfo`] together with the [`CallEntryPoint`] that was called.
+type CalledExecutionInfo = (CallEntryPoint, CairoExecutionInfo);
+
+/// Execution of a single call from the call trace.
+pub struct CallExecution {
+    /// Entry point that was called. Not available for traces written directly by the Cairo VM.
+    pub entry_point: Option<CallEntryPoint>,
+    pub casm_level_info: CasmLevelInfo,
+    /// Path of the trace file the execution was loaded from.
+    pub trace_path: Utf8PathBuf,
+}
+
+/// [`CallExecution`] together with the resolved path of the Sierra program it executed.
+type ProgramCallExecution = (Utf8PathBuf, CallExecution);
+
+/// Load the grouped [`CallExecution`]s together with the [`CallInteractions`] of all calls.
 /// # Optimization
 /// - We group them by `source_sierra_path` so that the same Sierra program does not need to be deserialized multiple times.
-/// - We grouped them in hashmap of `source_sierra_path` to `Vec<CasmLevelInfo>` not `Vec<CairoExecutionInfo>` to avoid cloning the `source_sierra_path` multiple times.
+/// - We grouped them in hashmap of `source_sierra_path` to `Vec<CallExecution>` not `Vec<CairoExecutionInfo>` to avoid cloning the `source_sierra_path` multiple times.
+///
+/// Trace files that cannot be read are recorded in `skipped_inputs`, just like the programs of the calls
+/// that point to missing Sierra files. Other calls from the same trace file are still loaded.
 pub fn load_grouped(
     call_trace_paths: &[Utf8PathBuf],
-) -> Result<HashMap<Utf8PathBuf, Vec<CasmLevelInfo>>> {
-    let call_traces = call_trace_paths
+    sierra_root: Option<&Utf8Path>,
+    skipped_inputs: &SkippedInputs,
+) -> Result<(GroupedCallExecutions, Vec<CallInteractions>)> {
+    let (execution_infos, call_interactions): (Vec<_>, Vec<_>) = call_trace_paths
         .par_iter()
-        .map(read_and_deserialize)
-        .collect::<Result<Vec<_>>>()?;
+        .map(|path| {
+            let execution_infos = load_resolved(path, sierra_root, skipped_inputs);
+            skipped_inputs.skip_on_error(InputKind::Trace, path, execution_infos)
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .unzip();
 
-    let execution_infos = call_traces
-        .into_par_iter()
-        .flat_map(load_cairo_execution_infos)
-        .collect();
+    Ok((
+        group_by_sierra_path(execution_infos.into_iter().flatten().collect()),
+        call_interactions.into_iter().flatten().collect(),
+    ))
+}
+
+/// Load the [`CallExecution`]s from the call trace at the given path
+/// with `source_sierra_path` resolved using [`sierra_path::resolve`],
+/// together with the [`CallInteractions`] of all calls from the trace.
+///
+/// Calls whose `source_sierra_path` cannot be resolved are recorded in `skipped_inputs` as programs.
+fn load_resolved(
+    call_trace_path: &Utf8PathBuf,
+    sierra_root: Option<&Utf8Path>,
+    skipped_inputs: &SkippedInputs,
+) -> Result<(Vec<ProgramCallExecution>, Vec<CallInteractions>)> {
+    let call_trace = read_and_deserialize(call_trace_path, InputKind::Trace)?;
+    let call_interactions = call_interactions::load(&call_trace, call_trace_path);
 
-    Ok(group_by_sierra_path(execution_infos))
+    let call_executions = load_cairo_execution_infos(call_trace)
+        .into_iter()
+        .map(|(entry_point, execution_info)| {
+            let source_sierra_path = skipped_inputs.skip_on_error(
+                InputKind::Program,
+                &execution_info.source_sierra_path,
+                sierra_path::resolve(
+                    &execution_info.source_sierra_path,
+                    call_trace_path,
+                    sierra_root,
+                ),
+            )?;
+            Ok(source_sierra_path.map(|source_sierra_path| {
+                let call_execution = CallExecution {
+                    entry_point: Some(entry_point),
+                    casm_level_info: execution_info.casm_level_info,
+                    trace_path: call_trace_path.clone(),
+                };
+                (source_sierra_path, call_execution)
+            }))
+        })
+        .filter_map(Result::transpose)
+        .collect::<Result<_>>()?;
+
+    Ok((call_executions, call_interactions))
 }
 
-/// Group the [`CairoExecutionInfo`] by `source_sierra_path`.
-fn group_by_sierra_path(
-    execution_infos: Vec<CairoExecutionInfo>,
-) -> HashMap<Utf8PathBuf, Vec<CasmLevelInfo>> {
-    execution_infos.into_iter().fold(
+/// Group the [`CallExecution`]s by `source_sierra_path`.
+fn group_by_sierra_path(call_executions: Vec<ProgramCallExecution>) -> GroupedCallExecutions {
+    call_executions.into_iter().fold(
         HashMap::new(),
-        |mut acc: HashMap<_, Vec<_>>, execution_info| {
-            acc.entry(execution_info.source_sierra_path)
+        |mut acc: HashMap<_, Vec<_>>, (source_sierra_path, call_execution)| {
+            acc.entry(source_sierra_path)
                 .or_default()
-                .push(execution_info.casm_level_info);
+                .push(call_execution);
             acc
         },
     )
 }
 
-/// Load the [`CairoExecutionInfo`] from the given call trace.
+/// Load the [`CairoExecutionInfo`] together with the called [`CallEntryPoint`] from the given call trace.
 /// As the [`CallTraceV1`] is nested, we need to load recursively.
 fn load_cairo_execution_infos(
     VersionedCallTrace::V1(call_trace): VersionedCallTrace,
-) -> Vec<CairoExecutionInfo> {
-    fn load_recursively(call_trace: CallTraceV1, acc: &mut Vec<CairoExecutionInfo>) {
+) -> Vec<CalledExecutionInfo> {
+    fn load_recursively(call_trace: CallTraceV1, acc: &mut Vec<CalledExecutionInfo>) {
         if let Some(execution_info) = call_trace.cairo_execution_info {
-            acc.push(execution_info);
+            acc.push((call_trace.entry_point, execution_info));
         }
 
         for call_trace_node in call_trace.nested_calls {
@@ -63,3 +132,60 @@ fn load_cairo_execution_infos(
     load_recursively(call_trace, &mut execution_infos);
     execution_infos
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use assert_fs::fixture::{FileWriteStr, PathChild};
+    use cairo_annotations::trace_data::{ExecutionResources, L1Resources};
+    use cairo_coverage_test_utils::{CreateFile, Utf8PathBufConversion};
+
+    fn call_trace(source_sierra_path: &Utf8Path, nested_calls: Vec<CallTraceV1>) -> CallTraceV1 {
+        CallTraceV1 {
+            entry_point: CallEntryPoint::default(),
+            cumulative_resources: ExecutionResources::default(),
+            used_l1_resources: L1Resources::default(),
+            nested_calls: nested_calls
+                .into_iter()
+                .map(|nested_call| CallTraceNode::EntryPointCall(Box::new(nested_call)))
+                .collect(),
+            cairo_execution_info: Some(CairoExecutionInfo {
+                source_sierra_path: source_sierra_path.to_path_buf(),
+                casm_level_info: CasmLevelInfo {
+                    run_with_call_header: false,
+                    vm_trace: Vec::new(),
+                    program_offset: None,
+                },
+                enable_gas: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_calls_with_missing_sierra_files_are_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let sierra_path = temp_dir
+            .create_file("program.sierra.json")
+            .canonicalize()
+            .unwrap()
+            .to_utf8_path_buf();
+        let missing_path = temp_dir.child("missing.sierra.json").to_utf8_path_buf();
+        let trace = temp_dir.child("trace.json");
+        let call_trace = call_trace(&sierra_path, vec![call_trace(&missing_path, vec![])]);
+        trace
+            .write_str(&serde_json::to_string(&VersionedCallTrace::V1(call_trace)).unwrap())
+            .unwrap();
+        let skipped_inputs = SkippedInputs::new(true);
+
+        let (grouped, _) =
+            load_grouped(&[trace.to_utf8_path_buf()], None, &skipped_inputs).unwrap();
+
+        assert_eq!(grouped.len(), 1);
+        assert_eq!(grouped[&sierra_path].len(), 1);
+        let skipped_inputs = skipped_inputs.into_sorted_vec();
+        assert_eq!(skipped_inputs.len(), 1);
+        assert_eq!(skipped_inputs[0].kind, InputKind::Program);
+        assert_eq!(skipped_inputs[0].path, missing_path);
+    }
+}
@@ -12,21 +12,134 @@
 //! - Prefer standalone functions over struct-based factory methods to
 //!   avoid wrapping or redefining external types unnecessarily.
 
-use anyhow::{Context, Result};
-use camino::Utf8PathBuf;
+use crate::error::Error;
+use crate::report::InputKind;
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use flate2::read::GzDecoder;
 use serde::de::DeserializeOwned;
 use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
 
+pub mod call_interactions;
 pub mod enriched_program;
+pub mod entry_points;
 pub mod execution_data;
-mod execution_infos;
+pub mod execution_infos;
+mod sierra_path;
+pub mod vm_trace;
 
-/// Utility function to read and deserialize a JSON file.
-fn read_and_deserialize<T: DeserializeOwned>(file_path: &Utf8PathBuf) -> Result<T> {
-    let content = fs::read_to_string(file_path)
-        .context(format!("failed to read file at path: {file_path}"))?;
+/// Utility function to read and deserialize a JSON file of the given [`InputKind`].
+/// Files with `.gz` or `.zst` extension are transparently decompressed.
+fn read_and_deserialize<T: DeserializeOwned>(
+    file_path: &Utf8PathBuf,
+    kind: InputKind,
+) -> Result<T> {
+    let content = read_to_string(file_path).map_err(|source| Error::ReadFile {
+        path: file_path.clone(),
+        source,
+    })?;
 
-    serde_json::from_str(&content).context(format!(
-        "failed to deserialize JSON content from file at path: {file_path}"
-    ))
+    serde_json::from_str(&content).map_err(|error| {
+        let (path, reason) = (file_path.clone(), error.to_string());
+        match kind {
+            InputKind::Trace => Error::InvalidTrace { path, reason },
+            InputKind::Program => Error::InvalidProgram { path, reason },
+        }
+        .into()
+    })
+}
+
+/// Read the whole file to a string, decompressing it first if needed.
+fn read_to_string(file_path: &Utf8Path) -> io::Result<String> {
+    String::from_utf8(read(file_path)?)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Read the whole file, decompressing it first if needed.
+fn read(file_path: &Utf8Path) -> io::Result<Vec<u8>> {
+    let mut content = Vec::new();
+    match file_path.extension() {
+        Some("gz") => GzDecoder::new(File::open(file_path)?).read_to_end(&mut content)?,
+        Some("zst") => zstd::Decoder::new(File::open(file_path)?)?.read_to_end(&mut content)?,
+        _ => return fs::read(file_path),
+    };
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use assert_fs::fixture::PathChild;
+    use cairo_coverage_test_utils::Utf8PathBufConversion;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    const CONTENT: &str = r#"{"key": "value"}"#;
+
+    fn deserialize_value(path: &Utf8PathBuf) -> String {
+        let json: serde_json::Value = read_and_deserialize(path, InputKind::Trace).unwrap();
+        json["key"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_reads_plain_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.child("trace.json").to_utf8_path_buf();
+        fs::write(&path, CONTENT).unwrap();
+
+        assert_eq!(deserialize_value(&path), "value");
+    }
+
+    #[test]
+    fn test_reads_gzip_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.child("trace.json.gz").to_utf8_path_buf();
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        encoder.write_all(CONTENT.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        assert_eq!(deserialize_value(&path), "value");
+    }
+
+    #[test]
+    fn test_reads_zstd_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.child("trace.json.zst").to_utf8_path_buf();
+        fs::write(&path, zstd::encode_all(CONTENT.as_bytes(), 0).unwrap()).unwrap();
+
+        assert_eq!(deserialize_value(&path), "value");
+    }
+
+    #[test]
+    fn test_invalid_json_is_reported_with_the_input_kind() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.child("program.sierra.json").to_utf8_path_buf();
+        fs::write(&path, "{").unwrap();
+
+        let result: Result<serde_json::Value> = read_and_deserialize(&path, InputKind::Program);
+
+        assert!(matches!(
+            result.unwrap_err().downcast(),
+            Ok(Error::InvalidProgram { path: error_path, .. }) if error_path == path
+        ));
+    }
+
+    #[test]
+    fn test_fails_on_corrupted_gzip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.child("trace.json.gz").to_utf8_path_buf();
+        fs::write(&path, CONTENT).unwrap();
+
+        let result: Result<serde_json::Value> = read_and_deserialize(&path, InputKind::Trace);
+
+        assert!(matches!(
+            result.unwrap_err().downcast(),
+            Ok(Error::ReadFile { .. })
+        ));
+    }
 }
@@ -0,0 +1,133 @@
+use crate::error::Error;
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use std::env;
+
+/// Resolve the `source_sierra_path` referenced by the trace file at `call_trace_path`.
+///
+/// Absolute paths are used as-is. Relative paths are looked up, in this order, against:
+/// - the `sierra_root` directory, if provided,
+/// - the directory containing the trace file,
+/// - the current working directory.
+///
+/// The first existing file is returned in its canonical form, so the same program referenced
+/// in different ways is still loaded only once.
+pub fn resolve(
+    source_sierra_path: &Utf8Path,
+    call_trace_path: &Utf8Path,
+    sierra_root: Option<&Utf8Path>,
+) -> Result<Utf8PathBuf> {
+    let candidates = candidates(source_sierra_path, call_trace_path, sierra_root);
+
+    if let Some(found) = candidates.iter().find(|candidate| candidate.is_file()) {
+        return Ok(found.canonicalize_utf8().unwrap_or_else(|_| found.clone()));
+    }
+
+    Err(Error::MissingSierraFile {
+        source_sierra_path: source_sierra_path.to_path_buf(),
+        trace_path: call_trace_path.to_path_buf(),
+        attempted_locations: candidates,
+    }
+    .into())
+}
+
+/// Paths at which the `source_sierra_path` is looked for, in order of priority.
+fn candidates(
+    source_sierra_path: &Utf8Path,
+    call_trace_path: &Utf8Path,
+    sierra_root: Option<&Utf8Path>,
+) -> Vec<Utf8PathBuf> {
+    if source_sierra_path.is_absolute() {
+        return vec![source_sierra_path.to_path_buf()];
+    }
+
+    let trace_dir = call_trace_path.parent().map(Utf8Path::to_path_buf);
+    let current_dir = env::current_dir()
+        .ok()
+        .and_then(|dir| Utf8PathBuf::from_path_buf(dir).ok());
+
+    sierra_root
+        .map(Utf8Path::to_path_buf)
+        .into_iter()
+        .chain(trace_dir)
+        .chain(current_dir)
+        .map(|dir| dir.join(source_sierra_path))
+        .unique()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use assert_fs::fixture::PathChild;
+    use cairo_coverage_test_utils::{CreateFile, Utf8PathBufConversion};
+
+    const SIERRA_FILE: &str = "target/dev/program.sierra.json";
+
+    #[test]
+    fn test_absolute_path_is_used_as_is() {
+        let temp_dir = TempDir::new().unwrap();
+        let sierra_file = temp_dir.create_file("program.sierra.json");
+        let sierra_path = sierra_file.canonicalize().unwrap().to_utf8_path_buf();
+
+        let result = resolve(&sierra_path, "trace/test.json".into(), None).unwrap();
+
+        assert_eq!(result, sierra_path);
+    }
+
+    #[test]
+    fn test_relative_path_is_resolved_against_trace_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let trace_dir = temp_dir.child("trace");
+        let trace_file = trace_dir.create_file("test.json").to_utf8_path_buf();
+        let sierra_file = trace_dir.create_file(SIERRA_FILE);
+
+        let result = resolve(SIERRA_FILE.into(), &trace_file, None).unwrap();
+
+        assert_eq!(
+            result,
+            sierra_file.canonicalize().unwrap().to_utf8_path_buf()
+        );
+    }
+
+    #[test]
+    fn test_sierra_root_takes_precedence_over_trace_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let trace_dir = temp_dir.child("trace");
+        let trace_file = trace_dir.create_file("test.json").to_utf8_path_buf();
+        trace_dir.create_file(SIERRA_FILE);
+        let sierra_root = temp_dir.child("artifacts");
+        let sierra_file = sierra_root.create_file(SIERRA_FILE);
+
+        let result = resolve(
+            SIERRA_FILE.into(),
+            &trace_file,
+            Some(&sierra_root.to_utf8_path_buf()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            sierra_file.canonicalize().unwrap().to_utf8_path_buf()
+        );
+    }
+
+    #[test]
+    fn test_missing_file_lists_attempted_locations() {
+        let temp_dir = TempDir::new().unwrap();
+        let trace_file = temp_dir.create_file("test.json").to_utf8_path_buf();
+        let sierra_root = temp_dir.child("artifacts").to_utf8_path_buf();
+
+        let error = resolve(SIERRA_FILE.into(), &trace_file, Some(&sierra_root))
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains(&format!("- {}", sierra_root.join(SIERRA_FILE))));
+        assert!(error.contains(&format!(
+            "- {}",
+            temp_dir.to_utf8_path_buf().join(SIERRA_FILE)
+        )));
+    }
+}
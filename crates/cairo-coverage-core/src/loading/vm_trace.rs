@@ -0,0 +1,261 @@
+use crate::args::VmTrace;
+use crate::error::Error;
+use crate::loading::execution_infos::{CallExecution, GroupedCallExecutions};
+use crate::loading::read;
+use crate::report::InputKind;
+use crate::skipped_inputs::SkippedInputs;
+use anyhow::Result;
+use cairo_annotations::trace_data::{CasmLevelInfo, TraceEntry};
+use camino::{Utf8Path, Utf8PathBuf};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+/// Size of a single entry in the binary trace: `ap`, `fp` and `pc`, each encoded as a little-endian `u64`.
+const TRACE_ENTRY_SIZE: usize = 3 * size_of::<u64>();
+
+/// Load the [`CallExecution`]s from the traces written directly by the Cairo VM, grouped by the program path.
+/// Each trace is treated as a single execution of the whole program.
+///
+/// Traces that cannot be read are recorded in `skipped_inputs`.
+pub fn load_grouped(
+    vm_traces: &[VmTrace],
+    skipped_inputs: &SkippedInputs,
+) -> Result<GroupedCallExecutions> {
+    let call_executions = vm_traces
+        .par_iter()
+        .map(|vm_trace| {
+            let call_execution = load_call_execution(vm_trace);
+            skipped_inputs.skip_on_error(InputKind::Trace, &vm_trace.trace, call_execution)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(call_executions.into_iter().flatten().fold(
+        GroupedCallExecutions::new(),
+        |mut acc, (program_path, call_execution)| {
+            acc.entry(program_path).or_default().push(call_execution);
+            acc
+        },
+    ))
+}
+
+/// Load the [`CallExecution`] from the [`VmTrace`] together with the canonical path of its program.
+fn load_call_execution(
+    VmTrace {
+        trace,
+        program,
+        program_offset,
+    }: &VmTrace,
+) -> Result<(Utf8PathBuf, CallExecution)> {
+    let vm_trace = load(trace)?;
+    let program_path = program
+        .canonicalize_utf8()
+        .map_err(|source| Error::MissingProgram {
+            path: program.clone(),
+            trace_path: trace.clone(),
+            source,
+        })?;
+
+    let call_execution = CallExecution {
+        entry_point: None,
+        casm_level_info: CasmLevelInfo {
+            // Runners like `cairo1-run` wrap the program in a header, which ends the execution.
+            run_with_call_header: program_offset.is_none(),
+            vm_trace,
+            program_offset: *program_offset,
+        },
+        trace_path: trace.clone(),
+    };
+
+    Ok((program_path, call_execution))
+}
+
+/// Load the [`TraceEntry`]s from the binary relocated trace file written by the Cairo VM,
+/// e.g. with the `--trace_file` option of `cairo1-run`.
+fn load(trace_path: &Utf8Path) -> Result<Vec<TraceEntry>> {
+    let content = read(trace_path).map_err(|source| Error::ReadFile {
+        path: trace_path.to_path_buf(),
+        source,
+    })?;
+
+    if content.len() % TRACE_ENTRY_SIZE != 0 {
+        return Err(Error::InvalidTrace {
+            path: trace_path.to_path_buf(),
+            reason: format!(
+                "not a valid Cairo VM trace, its size must be a multiple of {TRACE_ENTRY_SIZE} bytes"
+            ),
+        }
+        .into());
+    }
+
+    content
+        .chunks_exact(TRACE_ENTRY_SIZE)
+        .map(|entry| {
+            let value = |index: usize| -> Result<usize> {
+                let bytes = &entry[index * size_of::<u64>()..(index + 1) * size_of::<u64>()];
+                Ok(usize::try_from(u64::from_le_bytes(bytes.try_into()?))?)
+            };
+            Ok(TraceEntry {
+                ap: value(0)?,
+                fp: value(1)?,
+                pc: value(2)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use assert_fs::fixture::PathChild;
+    use cairo_annotations::{MappingResult, map_pcs_to_sierra_statement_ids};
+    use cairo_coverage_test_utils::Utf8PathBufConversion;
+    use cairo_lang_sierra::program::StatementIdx;
+    use cairo_lang_sierra_to_casm::compiler::{
+        CairoProgramDebugInfo, ReturnStatementDebugInfo, SierraStatementDebugInfo,
+        StatementKindDebugInfo,
+    };
+    use std::fs;
+
+    fn encode(entries: &[(u64, u64, u64)]) -> Vec<u8> {
+        entries
+            .iter()
+            .flat_map(|(ap, fp, pc)| [ap, fp, pc])
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    /// Statements 0, 1 and 2 compiled to the instructions at offsets 0..2, 2..5 and 5..6.
+    fn casm_debug_info() -> CairoProgramDebugInfo {
+        CairoProgramDebugInfo {
+            sierra_statement_info: [(0, 2), (2, 5), (5, 6)]
+                .into_iter()
+                .enumerate()
+                .map(
+                    |(instruction_idx, (start_offset, end_offset))| SierraStatementDebugInfo {
+                        start_offset,
+                        end_offset,
+                        instruction_idx,
+                        additional_kind_info: StatementKindDebugInfo::Return(
+                            ReturnStatementDebugInfo {
+                                ref_values: Vec::new(),
+                            },
+                        ),
+                    },
+                )
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_load_binary_trace() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.child("trace.bin").to_utf8_path_buf();
+        fs::write(&path, encode(&[(10, 10, 1), (11, 10, 3)])).unwrap();
+
+        let trace = load(&path).unwrap();
+
+        assert_eq!(
+            trace
+                .iter()
+                .map(|entry| (entry.ap, entry.fp, entry.pc))
+                .collect::<Vec<_>>(),
+            vec![(10, 10, 1), (11, 10, 3)]
+        );
+    }
+
+    #[test]
+    fn test_load_truncated_trace_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.child("trace.bin").to_utf8_path_buf();
+        let mut content = encode(&[(10, 10, 1)]);
+        content.pop();
+        fs::write(&path, content).unwrap();
+
+        assert!(load(&path).is_err());
+    }
+
+    #[test]
+    fn test_missing_program_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let trace = temp_dir.child("trace.bin").to_utf8_path_buf();
+        fs::write(&trace, encode(&[(10, 10, 1)])).unwrap();
+
+        let result = load_call_execution(&VmTrace {
+            trace,
+            program: temp_dir.child("missing.sierra.json").to_utf8_path_buf(),
+            program_offset: None,
+        });
+
+        assert!(matches!(
+            result.map_err(anyhow::Error::downcast::<Error>),
+            Err(Ok(Error::MissingProgram { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_pcs_are_mapped_to_statements_with_program_offset() {
+        let temp_dir = TempDir::new().unwrap();
+        let trace = temp_dir.child("trace.bin").to_utf8_path_buf();
+        let program = temp_dir.child("program.sierra.json").to_utf8_path_buf();
+        // The program starts after a header of 10 instructions, so its first instruction has pc 11.
+        fs::write(
+            &trace,
+            encode(&[(1, 1, 5), (1, 1, 11), (1, 1, 13), (1, 1, 16), (1, 1, 17)]),
+        )
+        .unwrap();
+        fs::write(&program, "").unwrap();
+
+        let (_, call_execution) = load_call_execution(&VmTrace {
+            trace,
+            program,
+            program_offset: Some(10),
+        })
+        .unwrap();
+        let mapping =
+            map_pcs_to_sierra_statement_ids(&casm_debug_info(), &call_execution.casm_level_info);
+
+        assert_eq!(
+            mapping,
+            vec![
+                MappingResult::Header,
+                MappingResult::SierraStatementIdx(StatementIdx(0)),
+                MappingResult::SierraStatementIdx(StatementIdx(1)),
+                MappingResult::SierraStatementIdx(StatementIdx(2)),
+                MappingResult::PcOutOfFunctionArea,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pcs_are_mapped_to_statements_after_header_ending_the_execution() {
+        let temp_dir = TempDir::new().unwrap();
+        let trace = temp_dir.child("trace.bin").to_utf8_path_buf();
+        let program = temp_dir.child("program.sierra.json").to_utf8_path_buf();
+        // The header calls the program at pc 4 and ends the execution at pc 3, which is the last entry of the trace.
+        fs::write(
+            &trace,
+            encode(&[(1, 1, 1), (1, 1, 4), (1, 1, 6), (1, 1, 9), (1, 1, 3)]),
+        )
+        .unwrap();
+        fs::write(&program, "").unwrap();
+
+        let (_, call_execution) = load_call_execution(&VmTrace {
+            trace,
+            program,
+            program_offset: None,
+        })
+        .unwrap();
+
+        assert!(call_execution.casm_level_info.run_with_call_header);
+        assert_eq!(
+            map_pcs_to_sierra_statement_ids(&casm_debug_info(), &call_execution.casm_level_info),
+            vec![
+                MappingResult::Header,
+                MappingResult::SierraStatementIdx(StatementIdx(0)),
+                MappingResult::SierraStatementIdx(StatementIdx(1)),
+                MappingResult::SierraStatementIdx(StatementIdx(2)),
+                MappingResult::Header,
+            ]
+        );
+    }
+}
@@ -0,0 +1,435 @@
+//! Structured coverage data returned by [`coverage`](crate::coverage).
+//!
+//! Unlike the internal representation used while building the coverage, every collection in this module
+//! is an owned [`Vec`] sorted in a stable order, so the data can be inspected, filtered and
+//! rendered with the formatters from the [`output`](crate::output) module.
+use crate::coverage::entry_point::EntryPointInvocations;
+use crate::coverage::file::FileCoverage as FileCoverageMap;
+use crate::coverage::program::ProgramCoverage as ProgramCoverageMap;
+use crate::coverage::project::ProjectCoverage;
+use crate::hashmap_utils::stable_iter::StableIter;
+use crate::report::SkippedInput;
+use cairo_annotations::trace_data::EntryPointType;
+use camino::Utf8PathBuf;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+pub use crate::build::filter::statement_category_filter::ExclusionReason;
+pub use crate::coverage::interactions::{CallKind, CallUsage, SyscallUsage};
+pub use crate::coverage::line_counts::LineCounts;
+pub use crate::loading::call_interactions::CallTarget;
+
+/// Coverage of the whole project.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Coverage {
+    /// Line coverage of each source file, merged across all programs. Sorted by the path.
+    pub files: Vec<FileCoverage>,
+    /// Coverage of each contract (or test executable). Sorted by the name and the artifact name.
+    pub programs: Vec<ProgramCoverage>,
+    /// Syscalls performed by the tests, sorted by the caller and the syscall name.
+    pub syscalls: Vec<SyscallUsage>,
+    /// Calls to other contracts made by the tests, sorted by the caller, the kind of the call and the callee.
+    pub calls: Vec<CallUsage>,
+    /// Coverage of every Sierra statement of each program artifact, sorted by the artifact path.
+    /// It is always empty unless `sierra_statements` is set in [`ReportOptions`](crate::args::ReportOptions).
+    pub statements: Vec<SierraCoverage>,
+    /// Inputs that could not be processed and were left out of the coverage.
+    /// It is always empty unless `keep_going` is set in [`RunOptions`](crate::args::RunOptions).
+    pub skipped_inputs: Vec<SkippedInput>,
+}
+
+/// Line coverage of a single source file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct FileCoverage {
+    /// Path of the source file as it appears in the reports.
+    pub path: String,
+    /// Functions defined in the file, sorted by the name.
+    pub functions: Vec<FunctionCoverage>,
+}
+
+/// Line coverage of a single function.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct FunctionCoverage {
+    /// Full path of the function, e.g. `package::module::function`.
+    pub name: String,
+    /// Lines of the function, sorted by the line number.
+    pub lines: Vec<LineCoverage>,
+}
+
+/// Hit count of a single line.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct LineCoverage {
+    /// Line number, starting from 1.
+    pub line: usize,
+    /// Number of times the line was executed, truncated to 1 unless `no_truncation` is set.
+    pub execution_count: usize,
+}
+
+/// Coverage of the code executed through a single contract (or test executable).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ProgramCoverage {
+    /// Name of the contract or, if it is not known, of the artifact the program was loaded from.
+    /// Contracts with the same name can be defined in different packages, use `artifact_name` to tell them apart.
+    pub name: String,
+    /// Name of the artifact the program was loaded from, e.g. `package_Contract`.
+    /// It is unique across the packages of the workspace.
+    pub artifact_name: String,
+    /// Line coverage of each source file, sorted by the path.
+    pub files: Vec<FileCoverage>,
+    /// Entry points of the contract. Empty if the program is not a contract.
+    pub entry_points: Vec<EntryPointCoverage>,
+}
+
+/// Entry point of a contract and the number of times it was invoked.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct EntryPointCoverage {
+    /// Whether it is a constructor, an external function or an L1 handler.
+    pub kind: EntryPointKind,
+    /// Name of the entry point from the ABI or the hex encoded selector if the ABI is not available.
+    pub name: String,
+    /// Hex encoded selector, e.g. `0x1a2b`.
+    pub selector: String,
+    /// Name of the Sierra function generated for the entry point (the `__wrapper__` function).
+    pub function: Option<String>,
+    /// Functions implementing the entry point: the generated wrapper and the user function it calls.
+    pub implementing_functions: Vec<String>,
+    /// Number of calls to the entry point recorded in the traces.
+    pub invocations: usize,
+}
+
+/// Coverage of every Sierra statement of a single program artifact.
+/// It shows how the line coverage was derived and is meant for debugging it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SierraCoverage {
+    pub source_sierra_path: Utf8PathBuf,
+    /// Sorted by the statement index.
+    pub statements: Vec<StatementCoverage>,
+}
+
+/// Coverage of a single Sierra statement.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct StatementCoverage {
+    /// Index of the statement in the Sierra program.
+    pub idx: usize,
+    /// Name of the invoked libfunc without the generic arguments, e.g. `store_temp`.
+    pub libfunc_name: Option<String>,
+    pub execution_count: usize,
+    /// Code location the statement is mapped to. `None` if the program has no debug information for it.
+    pub location: Option<StatementLocation>,
+    /// Reason the statement was excluded from the report. `None` if it is included.
+    pub exclusion_reason: Option<ExclusionReason>,
+}
+
+/// Code location of a Sierra statement.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct StatementLocation {
+    /// Path of the source file as it appears in the reports.
+    pub path: String,
+    /// Full path of the function the statement belongs to.
+    pub function: String,
+    /// First line of the code the statement was generated from.
+    pub start_line: usize,
+    /// Last line of the code the statement was generated from.
+    pub end_line: usize,
+}
+
+/// Kinds of contract entry points.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum EntryPointKind {
+    Constructor,
+    External,
+    L1Handler,
+}
+
+impl EntryPointKind {
+    /// Returns the name of the kind used in the reports.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            EntryPointKind::Constructor => "constructor",
+            EntryPointKind::External => "external",
+            EntryPointKind::L1Handler => "l1_handler",
+        }
+    }
+}
+
+impl Coverage {
+    /// Returns the [`LineCounts`] of all files.
+    #[must_use]
+    pub fn line_counts(&self) -> LineCounts {
+        self.files
+            .iter()
+            .map(FileCoverage::line_counts)
+            .fold(LineCounts::default(), |acc, line_counts| acc + line_counts)
+    }
+}
+
+impl FileCoverage {
+    /// Returns the lines of all functions in the file, sorted by the line number.
+    /// Hit counts of a line shared by several functions are summed.
+    #[must_use]
+    pub fn lines(&self) -> Vec<LineCoverage> {
+        self.functions
+            .iter()
+            .flat_map(|function| &function.lines)
+            .map(|line| (line.line, line.execution_count))
+            .into_grouping_map()
+            .sum()
+            .into_iter()
+            .sorted()
+            .map(|(line, execution_count)| LineCoverage {
+                line,
+                execution_count,
+            })
+            .collect()
+    }
+
+    /// Returns the [`LineCounts`] of the file.
+    #[must_use]
+    pub fn line_counts(&self) -> LineCounts {
+        LineCounts::from_execution_counts(self.lines().iter().map(|line| line.execution_count))
+    }
+
+    /// Returns the number of functions that were executed.
+    #[must_use]
+    pub fn executed_functions(&self) -> usize {
+        self.functions
+            .iter()
+            .filter(|function| function.was_executed())
+            .count()
+    }
+}
+
+impl FunctionCoverage {
+    /// Returns `true` if any line of the function was executed.
+    #[must_use]
+    pub fn was_executed(&self) -> bool {
+        self.lines.iter().any(|line| line.execution_count > 0)
+    }
+
+    /// Returns the maximum number of times a line of the function was executed.
+    #[must_use]
+    pub fn max_execution_count(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| line.execution_count)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Returns the line number where the function starts.
+    #[must_use]
+    pub fn starts_at(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| line.line)
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Returns the [`LineCounts`] of the function.
+    #[must_use]
+    pub fn line_counts(&self) -> LineCounts {
+        LineCounts::from_execution_counts(self.lines.iter().map(|line| line.execution_count))
+    }
+}
+
+impl EntryPointCoverage {
+    /// Returns the [`LineCounts`] of the functions implementing the entry point in the given files.
+    /// A line shared by several implementing functions is counted once.
+    #[must_use]
+    pub fn line_counts(&self, files: &[FileCoverage]) -> LineCounts {
+        let lines: HashMap<_, usize> = files
+            .iter()
+            .flat_map(|file| {
+                file.functions
+                    .iter()
+                    .filter(|function| self.implementing_functions.contains(&function.name))
+                    .flat_map(move |function| {
+                        function
+                            .lines
+                            .iter()
+                            .map(move |line| ((&file.path, line.line), line.execution_count))
+                    })
+            })
+            .into_grouping_map()
+            .max();
+
+        LineCounts::from_execution_counts(lines.into_values())
+    }
+}
+
+/// Creates the [`Coverage`] from the internal representation of the coverage data.
+pub(crate) fn create(
+    project_coverage: &ProjectCoverage,
+    programs: &[ProgramCoverageMap],
+    syscalls: Vec<SyscallUsage>,
+    calls: Vec<CallUsage>,
+    statements: Vec<SierraCoverage>,
+    skipped_inputs: Vec<SkippedInput>,
+) -> Coverage {
+    Coverage {
+        files: files(project_coverage),
+        programs: programs.iter().map(program).collect(),
+        syscalls,
+        calls,
+        statements,
+        skipped_inputs,
+    }
+}
+
+fn files(project_coverage: &ProjectCoverage) -> Vec<FileCoverage> {
+    project_coverage
+        .stable_iter()
+        .map(|(source_file_full_path, file_coverage)| {
+            file(source_file_full_path.to_string(), file_coverage)
+        })
+        .collect()
+}
+
+fn file(path: String, file_coverage: &FileCoverageMap) -> FileCoverage {
+    FileCoverage {
+        path,
+        functions: file_coverage
+            .stable_iter()
+            .map(|(name, function_coverage)| FunctionCoverage {
+                name: name.0.clone(),
+                lines: function_coverage
+                    .stable_iter()
+                    .map(|(line, execution_count)| LineCoverage {
+                        line: line.0,
+                        execution_count: *execution_count,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+fn program(program: &ProgramCoverageMap) -> ProgramCoverage {
+    ProgramCoverage {
+        name: program.name.clone(),
+        artifact_name: program.artifact_name.clone(),
+        files: files(&program.project_coverage),
+        entry_points: program
+            .entry_points
+            .entry_points
+            .iter()
+            .map(entry_point)
+            .collect(),
+    }
+}
+
+fn entry_point(
+    EntryPointInvocations {
+        entry_point,
+        invocations,
+    }: &EntryPointInvocations,
+) -> EntryPointCoverage {
+    EntryPointCoverage {
+        kind: match entry_point.kind {
+            EntryPointType::Constructor => EntryPointKind::Constructor,
+            EntryPointType::External => EntryPointKind::External,
+            EntryPointType::L1Handler => EntryPointKind::L1Handler,
+        },
+        name: entry_point.name.clone(),
+        selector: format!("{:#x}", entry_point.selector),
+        function: entry_point
+            .function_name
+            .as_ref()
+            .map(|function_name| function_name.0.clone()),
+        implementing_functions: entry_point
+            .implementing_functions
+            .iter()
+            .map(|function_name| function_name.0.clone())
+            .collect(),
+        invocations: *invocations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(name: &str, lines: &[(usize, usize)]) -> FunctionCoverage {
+        FunctionCoverage {
+            name: name.to_string(),
+            lines: lines
+                .iter()
+                .map(|&(line, execution_count)| LineCoverage {
+                    line,
+                    execution_count,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_file_lines_are_summed_across_functions() {
+        let file = FileCoverage {
+            path: "src/lib.cairo".to_string(),
+            functions: vec![
+                function("pkg::a", &[(1, 2), (2, 0)]),
+                function("pkg::b", &[(2, 3), (5, 0)]),
+            ],
+        };
+
+        assert_eq!(
+            file.lines(),
+            vec![
+                LineCoverage {
+                    line: 1,
+                    execution_count: 2
+                },
+                LineCoverage {
+                    line: 2,
+                    execution_count: 3
+                },
+                LineCoverage {
+                    line: 5,
+                    execution_count: 0
+                },
+            ]
+        );
+        assert_eq!(file.line_counts(), LineCounts { found: 3, hit: 2 });
+        assert_eq!(file.executed_functions(), 2);
+    }
+
+    #[test]
+    fn test_entry_point_line_counts_include_only_implementing_functions() {
+        let files = [FileCoverage {
+            path: "src/lib.cairo".to_string(),
+            functions: vec![
+                function("pkg::Contract::__wrapper__get", &[(3, 1)]),
+                function("pkg::Contract::get", &[(3, 1), (4, 0)]),
+                function("pkg::Contract::set", &[(8, 1)]),
+            ],
+        }];
+        let entry_point = EntryPointCoverage {
+            kind: EntryPointKind::External,
+            name: "get".to_string(),
+            selector: "0x1".to_string(),
+            function: Some("pkg::Contract::__wrapper__get".to_string()),
+            implementing_functions: vec![
+                "pkg::Contract::__wrapper__get".to_string(),
+                "pkg::Contract::get".to_string(),
+            ],
+            invocations: 1,
+        };
+
+        assert_eq!(
+            entry_point.line_counts(&files),
+            LineCounts { found: 2, hit: 1 }
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use crate::args::SourceLine;
+use crate::build::explanation::StatementExplanation;
+use crate::build::filter::statement_category_filter::FilterChecks;
+use crate::output::table::Table;
+use itertools::Itertools;
+use std::fmt;
+
+/// Formats the explanation of the coverage of a single source line as a string.
+/// It starts with the verdict, followed by the statements mapped to the line grouped by the program,
+/// with the results of every filter check and their execution counts.
+pub fn fmt_string(source_line: &SourceLine, explanations: &[StatementExplanation]) -> String {
+    let mut buf = String::new();
+    fmt(&mut buf, source_line, explanations)
+        .unwrap_or_else(|_| unreachable!("formatting to a string should never fail"));
+    buf
+}
+
+fn fmt(
+    writer: &mut impl fmt::Write,
+    source_line: &SourceLine,
+    explanations: &[StatementExplanation],
+) -> fmt::Result {
+    verdict(writer, source_line, explanations)?;
+
+    for (source_sierra_path, explanations) in &explanations
+        .iter()
+        .chunk_by(|explanation| &explanation.source_sierra_path)
+    {
+        writeln!(writer)?;
+        writeln!(writer, "statements of {source_sierra_path}")?;
+        statements(writer, explanations)?;
+    }
+
+    Ok(())
+}
+
+/// Writes whether the line is covered, taking only the reported statements into account.
+fn verdict(
+    writer: &mut impl fmt::Write,
+    SourceLine { file, line }: &SourceLine,
+    explanations: &[StatementExplanation],
+) -> fmt::Result {
+    let reported: Vec<_> = explanations
+        .iter()
+        .filter(|explanation| explanation.reported)
+        .collect();
+    let line_entries: usize = reported
+        .iter()
+        .map(|explanation| explanation.line_entries)
+        .sum();
+
+    if explanations.is_empty() {
+        writeln!(writer, "no statements are mapped to {file}:{line}")
+    } else if reported.is_empty() {
+        writeln!(
+            writer,
+            "{file}:{line} is not in the report, none of the statements mapped to it passed all the checks"
+        )
+    } else if line_entries > 0 {
+        writeln!(
+            writer,
+            "{file}:{line} is covered, it was entered {line_entries} time(s)"
+        )
+    } else {
+        writeln!(writer, "{file}:{line} is not covered")
+    }
+}
+
+/// Writes the statements of a single program mapped to the line.
+fn statements<'a>(
+    writer: &mut impl fmt::Write,
+    explanations: impl Iterator<Item = &'a StatementExplanation>,
+) -> fmt::Result {
+    let mut table = Table::new([
+        "statement",
+        "file",
+        "function",
+        "macro",
+        "libfunc",
+        "failed checks",
+        "reported",
+        "raw executions",
+        "executions",
+        "line entries",
+    ]);
+
+    for StatementExplanation {
+        idx,
+        function_name,
+        source_file_full_path,
+        is_macro,
+        libfunc_name,
+        filter_checks,
+        reported,
+        raw_execution_count,
+        execution_count,
+        line_entries,
+        ..
+    } in explanations
+    {
+        table.row([
+            idx.0.to_string(),
+            source_file_full_path.0.clone(),
+            function_name.0.clone(),
+            yes_no(*is_macro),
+            libfunc_name.clone().unwrap_or_else(|| "-".to_string()),
+            failed_checks(filter_checks),
+            yes_no(*reported),
+            raw_execution_count.to_string(),
+            execution_count.to_string(),
+            line_entries.to_string(),
+        ]);
+    }
+
+    table.fmt(writer)
+}
+
+/// Names of the failed checks, or `-` if all of them passed.
+fn failed_checks(filter_checks: &FilterChecks) -> String {
+    let failed = filter_checks
+        .iter()
+        .filter(|(_, passed)| !passed)
+        .map(|(reason, _)| reason.name())
+        .join(", ");
+    if failed.is_empty() {
+        "-".to_string()
+    } else {
+        failed
+    }
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build::filter::statement_category_filter::ExclusionReason;
+    use cairo_annotations::annotations::coverage::SourceFileFullPath;
+    use cairo_annotations::annotations::profiler::FunctionName;
+    use cairo_lang_sierra::program::StatementIdx;
+
+    fn explanation(
+        idx: usize,
+        reliable_libfunc: bool,
+        execution_count: usize,
+    ) -> StatementExplanation {
+        StatementExplanation {
+            source_sierra_path: "target/dev/pkg.sierra.json".into(),
+            idx: StatementIdx(idx),
+            function_name: FunctionName("pkg::add".to_string()),
+            source_file_full_path: SourceFileFullPath("/project/src/lib.cairo".to_string()),
+            is_macro: false,
+            libfunc_name: Some("felt252_add".to_string()),
+            filter_checks: vec![
+                (ExclusionReason::OutsideProject, true),
+                (ExclusionReason::UnreliableLibfunc, reliable_libfunc),
+            ],
+            reported: reliable_libfunc,
+            raw_execution_count: execution_count * 2,
+            execution_count,
+            line_entries: execution_count,
+        }
+    }
+
+    fn source_line() -> SourceLine {
+        SourceLine {
+            file: "src/lib.cairo".into(),
+            line: 3,
+        }
+    }
+
+    #[test]
+    fn test_explanation_counts_only_reported_statements() {
+        let output = fmt_string(
+            &source_line(),
+            &[explanation(4, true, 0), explanation(5, false, 7)],
+        );
+
+        assert!(output.starts_with("src/lib.cairo:3 is not covered\n"));
+        assert!(output.contains("statements of target/dev/pkg.sierra.json\n"));
+        assert!(output.lines().any(|line| {
+            line.split_whitespace().collect::<Vec<_>>()
+                == [
+                    "5",
+                    "/project/src/lib.cairo",
+                    "pkg::add",
+                    "no",
+                    "felt252_add",
+                    "unreliable",
+                    "libfunc",
+                    "no",
+                    "14",
+                    "7",
+                    "7",
+                ]
+        }));
+    }
+
+    #[test]
+    fn test_explanation_without_statements() {
+        assert_eq!(
+            fmt_string(&source_line(), &[]),
+            "no statements are mapped to src/lib.cairo:3\n"
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use crate::model::{
+    CallUsage, Coverage, EntryPointCoverage, FileCoverage, LineCounts, LineCoverage,
+    ProgramCoverage, SyscallUsage,
+};
+use serde::Serialize;
+use std::io;
+
+/// Formats the coverage data in the JSON format as a string.
+/// It contains everything that is available in the other formats in a structured form.
+#[must_use]
+pub fn fmt_string(coverage: &Coverage) -> String {
+    serde_json::to_string_pretty(&report(coverage))
+        .unwrap_or_else(|_| unreachable!("serializing the report should never fail"))
+}
+
+/// Writes the coverage data in the JSON format to the writer, followed by a newline, see [`fmt_string`].
+/// # Errors
+/// Fails if writing to the writer fails.
+pub fn write(writer: &mut dyn io::Write, coverage: &Coverage) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *writer, &report(coverage))?;
+    writeln!(writer)
+}
+
+fn report(coverage: &Coverage) -> JsonReport {
+    JsonReport {
+        files: coverage.files.iter().map(file).collect(),
+        programs: coverage.programs.iter().map(program).collect(),
+        syscalls: coverage.syscalls.iter().map(syscall).collect(),
+        calls: coverage.calls.iter().map(call).collect(),
+    }
+}
+
+#[derive(Serialize)]
+struct JsonReport {
+    files: Vec<JsonFile>,
+    programs: Vec<JsonProgram>,
+    syscalls: Vec<JsonSyscall>,
+    calls: Vec<JsonCall>,
+}
+
+#[derive(Serialize)]
+struct JsonFile {
+    path: String,
+    lines_hit: usize,
+    lines_found: usize,
+    functions: Vec<JsonFunction>,
+    lines: Vec<JsonLine>,
+}
+
+#[derive(Serialize)]
+struct JsonFunction {
+    name: String,
+    lines_hit: usize,
+    lines_found: usize,
+}
+
+#[derive(Serialize)]
+struct JsonLine {
+    line: usize,
+    execution_count: usize,
+}
+
+#[derive(Serialize)]
+struct JsonProgram {
+    name: String,
+    artifact_name: String,
+    files: Vec<JsonFile>,
+    entry_points: Vec<JsonEntryPoint>,
+}
+
+#[derive(Serialize)]
+struct JsonEntryPoint {
+    kind: &'static str,
+    name: String,
+    selector: String,
+    function: Option<String>,
+    invocations: usize,
+    lines_hit: usize,
+    lines_found: usize,
+}
+
+#[derive(Serialize)]
+struct JsonSyscall {
+    contract: String,
+    function: String,
+    syscall: String,
+    call_count: usize,
+    tests: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct JsonCall {
+    contract: String,
+    function: String,
+    kind: &'static str,
+    called_contract: String,
+    called_function: String,
+    call_count: usize,
+    tests: Vec<String>,
+}
+
+fn file(file_coverage: &FileCoverage) -> JsonFile {
+    let LineCounts { hit, found } = file_coverage.line_counts();
+
+    JsonFile {
+        path: file_coverage.path.clone(),
+        lines_hit: hit,
+        lines_found: found,
+        functions: file_coverage
+            .functions
+            .iter()
+            .map(|function_coverage| {
+                let LineCounts { hit, found } = function_coverage.line_counts();
+                JsonFunction {
+                    name: function_coverage.name.clone(),
+                    lines_hit: hit,
+                    lines_found: found,
+                }
+            })
+            .collect(),
+        lines: file_coverage
+            .lines()
+            .into_iter()
+            .map(
+                |LineCoverage {
+                     line,
+                     execution_count,
+                 }| JsonLine {
+                    line,
+                    execution_count,
+                },
+            )
+            .collect(),
+    }
+}
+
+fn program(program: &ProgramCoverage) -> JsonProgram {
+    JsonProgram {
+        name: program.name.clone(),
+        artifact_name: program.artifact_name.clone(),
+        files: program.files.iter().map(file).collect(),
+        entry_points: program
+            .entry_points
+            .iter()
+            .map(|entry_point_coverage| entry_point(entry_point_coverage, program))
+            .collect(),
+    }
+}
+
+fn entry_point(entry_point: &EntryPointCoverage, program: &ProgramCoverage) -> JsonEntryPoint {
+    let LineCounts { hit, found } = entry_point.line_counts(&program.files);
+
+    JsonEntryPoint {
+        kind: entry_point.kind.name(),
+        name: entry_point.name.clone(),
+        selector: entry_point.selector.clone(),
+        function: entry_point.function.clone(),
+        invocations: entry_point.invocations,
+        lines_hit: hit,
+        lines_found: found,
+    }
+}
+
+fn syscall(
+    SyscallUsage {
+        caller,
+        syscall,
+        call_count,
+        tests,
+    }: &SyscallUsage,
+) -> JsonSyscall {
+    JsonSyscall {
+        contract: caller.contract.clone(),
+        function: caller.function.clone(),
+        syscall: syscall.clone(),
+        call_count: *call_count,
+        tests: tests.iter().cloned().collect(),
+    }
+}
+
+fn call(
+    CallUsage {
+        caller,
+        kind,
+        callee,
+        call_count,
+        tests,
+    }: &CallUsage,
+) -> JsonCall {
+    JsonCall {
+        contract: caller.contract.clone(),
+        function: caller.function.clone(),
+        kind: kind.name(),
+        called_contract: callee.contract.clone(),
+        called_function: callee.function.clone(),
+        call_count: *call_count,
+        tests: tests.iter().cloned().collect(),
+    }
+}
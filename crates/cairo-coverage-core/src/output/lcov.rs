@@ -1,19 +1,25 @@
-use crate::coverage::file::{FileCoverage, FileCoverageOperations};
-use crate::coverage::function::FunctionCoverageOperations;
-use crate::coverage::project::ProjectCoverage;
-use crate::hashmap_utils::stable_iter::{IntoStableIter, StableIter};
-use cairo_annotations::annotations::coverage::SourceFileFullPath;
-use std::fmt;
+use crate::model::{FileCoverage, LineCounts, LineCoverage};
+use crate::output::write_io;
+use std::{fmt, io};
 
-/// Formats coverage data in the LCOV format as a string.
-pub fn fmt_string(project_coverage: &ProjectCoverage) -> String {
+/// Formats the line coverage of the given files in the LCOV format as a string,
+/// e.g. [`Coverage::files`](crate::model::Coverage::files) or the files of a single program.
+#[must_use]
+pub fn fmt_string(files: &[FileCoverage]) -> String {
     let mut buf = String::new();
     LcovFormatter::new(&mut buf)
-        .fmt(project_coverage)
+        .fmt(files)
         .unwrap_or_else(|_| unreachable!("formatting to a string should never fail"));
     buf
 }
 
+/// Writes the line coverage of the given files in the LCOV format to the writer, see [`fmt_string`].
+/// # Errors
+/// Fails if writing to the writer fails.
+pub fn write(writer: &mut dyn io::Write, files: &[FileCoverage]) -> io::Result<()> {
+    write_io(writer, |adapter| LcovFormatter::new(adapter).fmt(files))
+}
+
 /// Formats coverage data in the LCOV format to a writer.
 struct LcovFormatter<T: fmt::Write> {
     writer: T,
@@ -29,12 +35,12 @@ where
     }
 
     /// Formats the coverage data in the LCOV format.
-    fn fmt(&mut self, project_coverage: &ProjectCoverage) -> fmt::Result {
-        for (source_file_full_path, coverage_by_function) in project_coverage.stable_iter() {
-            self.general_information(source_file_full_path)?;
-            self.function_details(coverage_by_function)?;
-            self.function_summary(coverage_by_function)?;
-            self.line_execution(coverage_by_function)?;
+    fn fmt(&mut self, files: &[FileCoverage]) -> fmt::Result {
+        for file_coverage in files {
+            self.general_information(&file_coverage.path)?;
+            self.function_details(file_coverage)?;
+            self.function_summary(file_coverage)?;
+            self.line_execution(file_coverage)?;
             self.end_of_record()?;
         }
         Ok(())
@@ -43,7 +49,7 @@ where
     /// Writes the general:
     /// - TN(Test Name): accepted to be empty in the LCOV format
     /// - SF(Source File): source file
-    fn general_information(&mut self, source_file_full_path: &SourceFileFullPath) -> fmt::Result {
+    fn general_information(&mut self, source_file_full_path: &str) -> fmt::Result {
         writeln!(self.writer, "TN:")?;
         writeln!(self.writer, "SF:{source_file_full_path}")
     }
@@ -52,12 +58,13 @@ where
     /// - FN(Function Name): line at which function start, function name
     /// - FNDA(Function Data): how many times function was executed, function name
     fn function_details(&mut self, file_coverage: &FileCoverage) -> fmt::Result {
-        for (name, by_line) in file_coverage.stable_iter() {
-            writeln!(self.writer, "FN:{},{}", by_line.starts_at(), name)?;
+        for function_coverage in &file_coverage.functions {
+            let name = &function_coverage.name;
+            writeln!(self.writer, "FN:{},{}", function_coverage.starts_at(), name)?;
             writeln!(
                 self.writer,
                 "FNDA:{},{}",
-                by_line.max_execution_count(),
+                function_coverage.max_execution_count(),
                 name
             )?;
         }
@@ -69,7 +76,7 @@ where
     /// - FNF(Functions Found): number of functions found
     /// - FNH(Functions Hit): number of functions hit (executed)
     fn function_summary(&mut self, file_coverage: &FileCoverage) -> fmt::Result {
-        writeln!(self.writer, "FNF:{}", file_coverage.len())?;
+        writeln!(self.writer, "FNF:{}", file_coverage.functions.len())?;
         writeln!(self.writer, "FNH:{}", file_coverage.executed_functions())
     }
 
@@ -77,18 +84,21 @@ where
     /// - DA(Line Data): line number, execution count
     /// - LF (Lines Found): number of lines found
     /// - LH(Lines Hit): number of lines hit
-    fn line_execution(&mut self, coverage_by_function: &FileCoverage) -> fmt::Result {
-        let lines = coverage_by_function
-            .flatten()
-            .into_stable_iter()
-            .collect::<Vec<_>>();
+    fn line_execution(&mut self, file_coverage: &FileCoverage) -> fmt::Result {
+        let lines = file_coverage.lines();
 
-        for (line_number, execution_count) in &lines {
-            writeln!(self.writer, "DA:{line_number},{execution_count}")?;
+        for LineCoverage {
+            line,
+            execution_count,
+        } in &lines
+        {
+            writeln!(self.writer, "DA:{line},{execution_count}")?;
         }
 
-        writeln!(self.writer, "LF:{}", lines.len())?;
-        writeln!(self.writer, "LH:{}", coverage_by_function.executed_lines())
+        let LineCounts { found, hit } =
+            LineCounts::from_execution_counts(lines.iter().map(|line| line.execution_count));
+        writeln!(self.writer, "LF:{found}")?;
+        writeln!(self.writer, "LH:{hit}")
     }
 
     /// Writes the end of record marker.
@@ -1 +1,44 @@
+//! Formatters rendering the [`Coverage`](crate::model::Coverage) as strings or directly to streams
+//! and the [`Reporter`](reporter::Reporter)s using them.
+pub(crate) mod explanation;
+pub mod json;
 pub mod lcov;
+pub mod reporter;
+pub mod sierra;
+pub mod summary;
+mod table;
+
+use std::{fmt, io};
+
+/// Adapter passing the output of the formatters, which write to a [`fmt::Write`], to an [`io::Write`]
+/// without collecting it in a string first.
+pub(crate) struct IoAdapter<'a> {
+    writer: &'a mut dyn io::Write,
+    error: Option<io::Error>,
+}
+
+impl fmt::Write for IoAdapter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.writer.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+/// Run the formatter on the [`io::Write`] and return the I/O error that interrupted it, if any.
+pub(crate) fn write_io(
+    writer: &mut dyn io::Write,
+    format: impl FnOnce(&mut IoAdapter) -> fmt::Result,
+) -> io::Result<()> {
+    let mut adapter = IoAdapter {
+        writer,
+        error: None,
+    };
+    format(&mut adapter).map_err(|fmt::Error| {
+        adapter
+            .error
+            .take()
+            .unwrap_or_else(|| io::Error::other("formatting the report failed"))
+    })
+}
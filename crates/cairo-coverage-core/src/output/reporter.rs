@@ -0,0 +1,182 @@
+use crate::model::Coverage;
+use crate::output::{json, lcov, sierra, summary};
+use std::io;
+
+/// Where a report is written when no destination is chosen for it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum DefaultDestination {
+    /// The standard output.
+    Stdout,
+    /// The main output file of the run, e.g. `coverage.lcov`, to which the report is appended.
+    OutputFile,
+}
+
+/// Format in which the [`Coverage`] can be reported.
+/// Implement it to add a custom format to the [`Registry`].
+pub trait Reporter {
+    /// Name used to select the format, e.g. `lcov`.
+    fn name(&self) -> &str;
+
+    /// Returns where the report is written when no destination is chosen for it.
+    fn default_destination(&self) -> DefaultDestination {
+        DefaultDestination::Stdout
+    }
+
+    /// Returns `true` if the report uses [`Coverage::statements`],
+    /// which are only collected when `sierra_statements` is set in [`ReportOptions`](crate::args::ReportOptions).
+    fn requires_statements(&self) -> bool {
+        false
+    }
+
+    /// Writes the report of the [`Coverage`] to the writer, as it is formatted.
+    /// # Errors
+    /// Fails if writing to the writer fails.
+    fn write(&self, coverage: &Coverage, writer: &mut dyn io::Write) -> io::Result<()>;
+}
+
+/// Reports the line coverage of the project in the LCOV format.
+pub struct LcovReporter;
+
+impl Reporter for LcovReporter {
+    fn name(&self) -> &'static str {
+        "lcov"
+    }
+
+    fn default_destination(&self) -> DefaultDestination {
+        DefaultDestination::OutputFile
+    }
+
+    fn write(&self, coverage: &Coverage, writer: &mut dyn io::Write) -> io::Result<()> {
+        lcov::write(writer, &coverage.files)
+    }
+}
+
+/// Reports the human-readable summary, see [`summary::write`].
+pub struct SummaryReporter;
+
+impl Reporter for SummaryReporter {
+    fn name(&self) -> &'static str {
+        "summary"
+    }
+
+    fn write(&self, coverage: &Coverage, writer: &mut dyn io::Write) -> io::Result<()> {
+        summary::write(writer, coverage)
+    }
+}
+
+/// Reports all the coverage data in the JSON format, see [`json::write`].
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn write(&self, coverage: &Coverage, writer: &mut dyn io::Write) -> io::Result<()> {
+        json::write(writer, coverage)
+    }
+}
+
+/// Reports the coverage of every Sierra statement, see [`Coverage::statements`].
+pub struct SierraReporter;
+
+impl Reporter for SierraReporter {
+    fn name(&self) -> &'static str {
+        "sierra"
+    }
+
+    fn requires_statements(&self) -> bool {
+        true
+    }
+
+    fn write(&self, coverage: &Coverage, writer: &mut dyn io::Write) -> io::Result<()> {
+        sierra::write(writer, &coverage.statements)
+    }
+}
+
+/// Collection of [`Reporter`]s selectable by their names.
+/// The default registry contains the built-in `lcov`, `summary`, `json` and `sierra` formats.
+pub struct Registry {
+    reporters: Vec<Box<dyn Reporter>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self {
+            reporters: vec![
+                Box::new(LcovReporter),
+                Box::new(SummaryReporter),
+                Box::new(JsonReporter),
+                Box::new(SierraReporter),
+            ],
+        }
+    }
+}
+
+impl Registry {
+    /// Creates a [`Registry`] without any [`Reporter`]s.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            reporters: Vec::new(),
+        }
+    }
+
+    /// Adds the [`Reporter`] to the registry, replacing the one with the same name, if any.
+    pub fn register(&mut self, reporter: impl Reporter + 'static) {
+        self.reporters
+            .retain(|registered| registered.name() != reporter.name());
+        self.reporters.push(Box::new(reporter));
+    }
+
+    /// Returns the [`Reporter`] with the given name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&dyn Reporter> {
+        self.reporters
+            .iter()
+            .find(|reporter| reporter.name() == name)
+            .map(AsRef::as_ref)
+    }
+
+    /// Returns the names of all registered [`Reporter`]s in the order they were registered.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.reporters.iter().map(|reporter| reporter.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CustomReporter;
+
+    impl Reporter for CustomReporter {
+        fn name(&self) -> &'static str {
+            "lcov"
+        }
+
+        fn write(&self, coverage: &Coverage, writer: &mut dyn io::Write) -> io::Result<()> {
+            writeln!(writer, "{} files", coverage.files.len())
+        }
+    }
+
+    #[test]
+    fn test_registered_reporter_replaces_the_one_with_the_same_name() {
+        let mut registry = Registry::default();
+        registry.register(CustomReporter);
+
+        let mut output = Vec::new();
+        registry
+            .get("lcov")
+            .unwrap()
+            .write(&Coverage::default(), &mut output)
+            .unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "0 files\n");
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            ["summary", "json", "sierra", "lcov"]
+        );
+    }
+}
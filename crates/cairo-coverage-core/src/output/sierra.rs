@@ -0,0 +1,153 @@
+use crate::model::{SierraCoverage, StatementCoverage, StatementLocation};
+use crate::output::table::Table;
+use crate::output::write_io;
+use std::{fmt, io};
+
+/// Formats the coverage of every Sierra statement as a string.
+/// For each program artifact, it lists the statements with their libfunc, execution count, code location
+/// and whether they are included in the report, or why they were excluded.
+#[must_use]
+pub fn fmt_string(sierra_coverages: &[SierraCoverage]) -> String {
+    let mut buf = String::new();
+    fmt(&mut buf, sierra_coverages)
+        .unwrap_or_else(|_| unreachable!("formatting to a string should never fail"));
+    buf
+}
+
+/// Writes the coverage of every Sierra statement to the writer, see [`fmt_string`].
+/// # Errors
+/// Fails if writing to the writer fails.
+pub fn write(writer: &mut dyn io::Write, sierra_coverages: &[SierraCoverage]) -> io::Result<()> {
+    write_io(writer, |adapter| fmt(adapter, sierra_coverages))
+}
+
+fn fmt(writer: &mut impl fmt::Write, sierra_coverages: &[SierraCoverage]) -> fmt::Result {
+    for (index, sierra_coverage) in sierra_coverages.iter().enumerate() {
+        if index > 0 {
+            writeln!(writer)?;
+        }
+        statements(writer, sierra_coverage)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the statements of a single program artifact.
+fn statements(
+    writer: &mut impl fmt::Write,
+    SierraCoverage {
+        source_sierra_path,
+        statements,
+    }: &SierraCoverage,
+) -> fmt::Result {
+    writeln!(writer, "statements of {source_sierra_path}")?;
+
+    let mut table = Table::new([
+        "statement",
+        "libfunc",
+        "executions",
+        "location",
+        "function",
+        "status",
+    ]);
+
+    for StatementCoverage {
+        idx,
+        libfunc_name,
+        execution_count,
+        location,
+        exclusion_reason,
+    } in statements
+    {
+        let (location_cell, function) = match location {
+            Some(location) => (fmt_location(location), location.function.clone()),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        let status = match (location, exclusion_reason) {
+            (None, _) => "no debug information".to_string(),
+            (Some(_), None) => "included".to_string(),
+            (Some(_), Some(reason)) => format!("excluded: {}", reason.name()),
+        };
+
+        table.row([
+            idx.to_string(),
+            libfunc_name.clone().unwrap_or_else(|| "-".to_string()),
+            execution_count.to_string(),
+            location_cell,
+            function,
+            status,
+        ]);
+    }
+
+    table.fmt(writer)
+}
+
+/// Formats the location as `path:line` or `path:start-end` if the statement spans multiple lines.
+fn fmt_location(
+    StatementLocation {
+        path,
+        start_line,
+        end_line,
+        ..
+    }: &StatementLocation,
+) -> String {
+    if start_line == end_line {
+        format!("{path}:{start_line}")
+    } else {
+        format!("{path}:{start_line}-{end_line}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ExclusionReason;
+
+    fn statement(
+        idx: usize,
+        line: usize,
+        exclusion_reason: Option<ExclusionReason>,
+    ) -> StatementCoverage {
+        StatementCoverage {
+            idx,
+            libfunc_name: Some("felt252_add".to_string()),
+            execution_count: 2,
+            location: Some(StatementLocation {
+                path: "src/lib.cairo".to_string(),
+                function: "pkg::add".to_string(),
+                start_line: line,
+                end_line: line,
+            }),
+            exclusion_reason,
+        }
+    }
+
+    #[test]
+    fn test_statements_report_status() {
+        let sierra_coverage = SierraCoverage {
+            source_sierra_path: "target/dev/pkg.sierra.json".into(),
+            statements: vec![
+                statement(0, 3, None),
+                statement(1, 4, Some(ExclusionReason::UnreliableLibfunc)),
+                StatementCoverage {
+                    idx: 2,
+                    libfunc_name: Some("return".to_string()),
+                    execution_count: 0,
+                    location: None,
+                    exclusion_reason: None,
+                },
+            ],
+        };
+
+        assert_eq!(
+            fmt_string(&[sierra_coverage]),
+            indoc::indoc! {"
+                statements of target/dev/pkg.sierra.json
+                statement  libfunc      executions  location         function  status
+                0          felt252_add  2           src/lib.cairo:3  pkg::add  included
+                1          felt252_add  2           src/lib.cairo:4  pkg::add  excluded: unreliable libfunc
+                2          return       0           -                -         no debug information
+            "}
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use crate::model::{CallUsage, Coverage, FileCoverage, LineCounts, ProgramCoverage, SyscallUsage};
+use crate::output::table::Table;
+use crate::output::write_io;
+use std::{fmt, io};
+
+/// Formats a human-readable summary of the coverage data as a string.
+/// It contains the line coverage of each file, followed by the breakdown by program:
+/// the lines covered through each contract (or test executable) and the entry points of each contract.
+/// Syscalls and calls to other contracts performed by the tests are listed at the end.
+#[must_use]
+pub fn fmt_string(coverage: &Coverage) -> String {
+    let mut buf = String::new();
+    fmt(&mut buf, coverage)
+        .unwrap_or_else(|_| unreachable!("formatting to a string should never fail"));
+    buf
+}
+
+/// Writes the summary of the coverage data to the writer, see [`fmt_string`].
+/// # Errors
+/// Fails if writing to the writer fails.
+pub fn write(writer: &mut dyn io::Write, coverage: &Coverage) -> io::Result<()> {
+    write_io(writer, |adapter| fmt(adapter, coverage))
+}
+
+fn fmt(writer: &mut impl fmt::Write, coverage: &Coverage) -> fmt::Result {
+    files(writer, &coverage.files)?;
+
+    for program in &coverage.programs {
+        writeln!(writer)?;
+        writeln!(writer, "files covered through {}", program.name)?;
+        files(writer, &program.files)?;
+
+        if !program.entry_points.is_empty() {
+            writeln!(writer)?;
+            entry_points(writer, program)?;
+        }
+    }
+
+    if !coverage.syscalls.is_empty() {
+        writeln!(writer)?;
+        syscalls(writer, &coverage.syscalls)?;
+    }
+
+    if !coverage.calls.is_empty() {
+        writeln!(writer)?;
+        calls(writer, &coverage.calls)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the line coverage of each file and the total.
+fn files(writer: &mut impl fmt::Write, files: &[FileCoverage]) -> fmt::Result {
+    let mut table = Table::new(["file", "lines hit", "lines found", "coverage"]);
+    let mut total = LineCounts::default();
+
+    for file_coverage in files {
+        let line_counts = file_coverage.line_counts();
+        total = total + line_counts;
+        table.row(line_counts_row(file_coverage.path.clone(), line_counts));
+    }
+    table.row(line_counts_row("total".to_string(), total));
+
+    table.fmt(writer)
+}
+
+/// Writes the entry points of the contract with their invocations and line coverage.
+fn entry_points(
+    writer: &mut impl fmt::Write,
+    ProgramCoverage {
+        name,
+        files,
+        entry_points,
+        ..
+    }: &ProgramCoverage,
+) -> fmt::Result {
+    writeln!(writer, "entry points of contract {name}")?;
+
+    let mut table = Table::new([
+        "type",
+        "name",
+        "invocations",
+        "lines hit",
+        "lines found",
+        "coverage",
+    ]);
+
+    for entry_point in entry_points {
+        let line_counts = entry_point.line_counts(files);
+        let [lines_hit, lines_found, coverage] = line_counts_cells(line_counts);
+        table.row([
+            entry_point.kind.name().to_string(),
+            entry_point.name.clone(),
+            entry_point.invocations.to_string(),
+            lines_hit,
+            lines_found,
+            coverage,
+        ]);
+    }
+
+    table.fmt(writer)
+}
+
+/// Writes the syscalls performed by each function and the number of tests that performed them.
+fn syscalls(writer: &mut impl fmt::Write, syscalls: &[SyscallUsage]) -> fmt::Result {
+    writeln!(writer, "syscalls")?;
+
+    let mut table = Table::new(["contract", "function", "syscall", "calls", "tests"]);
+    for SyscallUsage {
+        caller,
+        syscall,
+        call_count,
+        tests,
+    } in syscalls
+    {
+        table.row([
+            caller.contract.clone(),
+            caller.function.clone(),
+            syscall.clone(),
+            call_count.to_string(),
+            tests.len().to_string(),
+        ]);
+    }
+
+    table.fmt(writer)
+}
+
+/// Writes the calls to other contracts made by each function and the number of tests that made them.
+fn calls(writer: &mut impl fmt::Write, calls: &[CallUsage]) -> fmt::Result {
+    writeln!(writer, "calls to other contracts")?;
+
+    let mut table = Table::new([
+        "contract",
+        "function",
+        "kind",
+        "called contract",
+        "called function",
+        "calls",
+        "tests",
+    ]);
+    for CallUsage {
+        caller,
+        kind,
+        callee,
+        call_count,
+        tests,
+    } in calls
+    {
+        table.row([
+            caller.contract.clone(),
+            caller.function.clone(),
+            kind.name().to_string(),
+            callee.contract.clone(),
+            callee.function.clone(),
+            call_count.to_string(),
+            tests.len().to_string(),
+        ]);
+    }
+
+    table.fmt(writer)
+}
+
+fn line_counts_row(name: String, line_counts: LineCounts) -> [String; 4] {
+    let [lines_hit, lines_found, coverage] = line_counts_cells(line_counts);
+    [name, lines_hit, lines_found, coverage]
+}
+
+fn line_counts_cells(line_counts: LineCounts) -> [String; 3] {
+    [
+        line_counts.hit.to_string(),
+        line_counts.found.to_string(),
+        format!("{:.2}%", line_counts.percentage()),
+    ]
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+/// Plain text table with left-aligned columns, used by the human-readable formats.
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Creates a new [`Table`] with the given column names.
+    pub fn new(header: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            header: header.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Appends a row. It should have the same number of cells as the header.
+    pub fn row(&mut self, row: impl IntoIterator<Item = impl Into<String>>) {
+        self.rows.push(row.into_iter().map(Into::into).collect());
+    }
+
+    /// Writes the table with columns padded to the widest cell.
+    pub fn fmt(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        let widths = self.header.iter().enumerate().map(|(column, name)| {
+            self.rows
+                .iter()
+                .filter_map(|row| row.get(column))
+                .map(String::len)
+                .fold(name.len(), usize::max)
+        });
+        let widths: Vec<_> = widths.collect();
+
+        for row in std::iter::once(&self.header).chain(&self.rows) {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(writer, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_are_aligned() {
+        let mut table = Table::new(["name", "count"]);
+        table.row(["increase_balance", "3"]);
+        table.row(["get", "10"]);
+
+        let mut buf = String::new();
+        table.fmt(&mut buf).unwrap();
+
+        assert_eq!(
+            buf,
+            "name              count\nincrease_balance  3\nget               10\n"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use camino::Utf8PathBuf;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Event reported through the [`ProgressCallback`] while the coverage is computed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ProgressEvent {
+    /// All traces were loaded together with the programs they executed.
+    TracesLoaded { traces: usize, programs: usize },
+    /// A program was compiled to CASM.
+    ProgramCompiled { path: Utf8PathBuf },
+    /// The coverage of a program was computed.
+    ProgramProcessed { path: Utf8PathBuf },
+    /// A stage of the computation finished.
+    /// Stages done for each program in parallel are reported once per program.
+    StageFinished { stage: Stage, duration: Duration },
+}
+
+/// Stages of the computation of the coverage.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum Stage {
+    /// Reading and deserializing the traces and programs.
+    Loading,
+    /// Compiling the programs to CASM.
+    CasmCompilation,
+    /// Building the filter deciding which statements are included in the report
+    /// and applying it to the code locations of all statements.
+    Filtering,
+    /// Mapping the executed statements to the lines and counting their hits.
+    StatementMapping,
+    /// Merging the coverage of all programs.
+    Merging,
+    /// Formatting the report.
+    Formatting,
+}
+
+impl Stage {
+    /// Returns the name of the stage used in the reports.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Loading => "loading",
+            Stage::CasmCompilation => "casm compilation",
+            Stage::Filtering => "filtering",
+            Stage::StatementMapping => "statement mapping",
+            Stage::Merging => "merging",
+            Stage::Formatting => "formatting",
+        }
+    }
+}
+
+/// Callback receiving the [`ProgressEvent`]s. It is called from multiple threads.
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(ProgressEvent) + Send + Sync>);
+
+impl ProgressCallback {
+    /// Creates a [`ProgressCallback`] calling the given function.
+    pub fn new(callback: impl Fn(ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+/// Reports the [`ProgressEvent`]s to the optional [`ProgressCallback`].
+pub(crate) struct Progress(Option<ProgressCallback>);
+
+impl Progress {
+    pub fn new(callback: Option<ProgressCallback>) -> Self {
+        Self(callback)
+    }
+
+    /// Reports the event, if there is a callback.
+    pub fn report(&self, event: ProgressEvent) {
+        if let Some(ProgressCallback(callback)) = &self.0 {
+            callback(event);
+        }
+    }
+
+    /// Runs the function and reports how long it took as the given [`Stage`].
+    pub fn time<T>(&self, stage: Stage, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.report(ProgressEvent::StageFinished {
+            stage,
+            duration: start.elapsed(),
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_time_reports_finished_stage() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let progress = Progress::new(Some(ProgressCallback::new({
+            let events = events.clone();
+            move |event| events.lock().unwrap().push(event)
+        })));
+
+        let result = progress.time(Stage::Merging, || 42);
+
+        assert_eq!(result, 42);
+        let events = events.lock().unwrap();
+        assert!(matches!(
+            events.as_slice(),
+            [ProgressEvent::StageFinished {
+                stage: Stage::Merging,
+                ..
+            }]
+        ));
+    }
+}
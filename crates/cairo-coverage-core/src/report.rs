@@ -0,0 +1,57 @@
+use camino::Utf8PathBuf;
+use std::fmt;
+use std::fmt::Display;
+
+/// Result of the `cairo_coverage_core` `run` and `explain` functions.
+#[non_exhaustive]
+pub struct Report {
+    /// Coverage report in the LCOV format or the explanation of the line.
+    pub output: String,
+    /// Inputs that could not be processed and were left out of the report.
+    /// It is always empty unless `keep_going` is set in [`RunOptions`](crate::args::RunOptions).
+    pub skipped_inputs: Vec<SkippedInput>,
+    /// Coverage reports of each contract (or test executable) in the LCOV format, sorted by the name.
+    /// It is always empty unless `split_by_contract` is set in [`ReportOptions`](crate::args::ReportOptions).
+    pub contract_reports: Vec<ContractReport>,
+}
+
+/// Coverage report of a single contract (or test executable).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ContractReport {
+    /// Name of the contract or, if it is not known, of the artifact it was loaded from.
+    pub name: String,
+    /// Coverage report of the code executed through the contract in the LCOV format.
+    pub lcov: String,
+}
+
+/// Input that was skipped because it could not be processed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SkippedInput {
+    /// Kind of the skipped input.
+    pub kind: InputKind,
+    /// Path to the skipped input.
+    pub path: Utf8PathBuf,
+    /// Human-readable reason why the input was skipped.
+    pub reason: String,
+}
+
+/// Kinds of inputs that can be skipped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum InputKind {
+    /// Trace file passed to the `run` function.
+    Trace,
+    /// Sierra program referenced by the trace files.
+    Program,
+}
+
+impl Display for InputKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputKind::Trace => write!(f, "trace"),
+            InputKind::Program => write!(f, "program"),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Project setup required by `cairo-coverage`, shared by the tools configuring and checking projects.
+use itertools::Itertools;
+
+/// Name of the file in the project directory listing the paths excluded from the coverage report,
+/// in the `.gitignore` format.
+pub const CAIRO_COVERAGE_IGNORE: &str = ".cairo-coverage-ignore";
+
+/// Entries of the `[profile.<name>.cairo]` section of `Scarb.toml` required to compute the coverage.
+pub const REQUIRED_COMPILER_SETTINGS: [CompilerSetting; 3] = [
+    CompilerSetting {
+        name: "unstable-add-statements-functions-debug-info",
+        value: "true",
+    },
+    CompilerSetting {
+        name: "unstable-add-statements-code-locations-debug-info",
+        value: "true",
+    },
+    CompilerSetting {
+        name: "inlining-strategy",
+        value: "\"avoid\"",
+    },
+];
+
+/// Entry of the `[profile.<name>.cairo]` section of `Scarb.toml`.
+#[derive(Debug, Eq, PartialEq)]
+pub struct CompilerSetting {
+    /// Name of the entry as written in `Scarb.toml`.
+    pub name: &'static str,
+    /// Required value of the entry as written in `Scarb.toml`.
+    pub value: &'static str,
+}
+
+/// Returns the `[profile.<profile>.cairo]` section of `Scarb.toml` with the given settings.
+#[must_use]
+pub fn profile_toml<'a>(
+    profile: &str,
+    settings: impl IntoIterator<Item = &'a CompilerSetting>,
+) -> String {
+    format!(
+        "[profile.{profile}.cairo]\n{}\n",
+        settings
+            .into_iter()
+            .map(|setting| format!("{} = {}", setting.name, setting.value))
+            .join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_toml() {
+        assert_eq!(
+            profile_toml("dev", &REQUIRED_COMPILER_SETTINGS),
+            "[profile.dev.cairo]\nunstable-add-statements-functions-debug-info = true\nunstable-add-statements-code-locations-debug-info = true\ninlining-strategy = \"avoid\"\n"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::report::{InputKind, SkippedInput};
+use anyhow::Result;
+use camino::Utf8Path;
+use std::sync::Mutex;
+
+/// Collector of inputs that failed to be processed.
+/// It is shared between threads, so it can be used directly inside parallel iterators.
+pub struct SkippedInputs {
+    keep_going: bool,
+    inputs: Mutex<Vec<SkippedInput>>,
+}
+
+impl SkippedInputs {
+    /// Create a new [`SkippedInputs`].
+    /// If `keep_going` is not set, no input is ever skipped and errors are propagated instead.
+    pub fn new(keep_going: bool) -> Self {
+        Self {
+            keep_going,
+            inputs: Mutex::default(),
+        }
+    }
+
+    /// Pass through the `result` of processing the input at `path`.
+    /// If it is an error and `keep_going` is set, the input is recorded as skipped and `Ok(None)` is returned.
+    pub fn skip_on_error<T>(
+        &self,
+        kind: InputKind,
+        path: &Utf8Path,
+        result: Result<T>,
+    ) -> Result<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if self.keep_going => {
+                self.inputs
+                    .lock()
+                    .unwrap_or_else(|_| unreachable!("no code panics while holding the lock"))
+                    .push(SkippedInput {
+                        kind,
+                        path: path.to_path_buf(),
+                        reason: format!("{error:#}"),
+                    });
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Return all skipped inputs in a deterministic order.
+    /// The same input skipped for the same reason multiple times, e.g. by many calls in a trace, is returned once.
+    pub fn into_sorted_vec(self) -> Vec<SkippedInput> {
+        let mut inputs = self
+            .inputs
+            .into_inner()
+            .unwrap_or_else(|_| unreachable!("no code panics while holding the lock"));
+        inputs.sort_by(|a, b| (a.kind, &a.path, &a.reason).cmp(&(b.kind, &b.path, &b.reason)));
+        inputs.dedup();
+        inputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_errors_are_propagated_without_keep_going() {
+        let skipped_inputs = SkippedInputs::new(false);
+
+        let result: Result<Option<()>> =
+            skipped_inputs.skip_on_error(InputKind::Trace, "a.json".into(), Err(anyhow!("boom")));
+
+        assert!(result.is_err());
+        assert!(skipped_inputs.into_sorted_vec().is_empty());
+    }
+
+    #[test]
+    fn test_errors_are_recorded_with_keep_going() {
+        let skipped_inputs = SkippedInputs::new(true);
+
+        let ok = skipped_inputs.skip_on_error(InputKind::Trace, "a.json".into(), Ok(1));
+        let skipped: Result<Option<i32>> = skipped_inputs.skip_on_error(
+            InputKind::Program,
+            "b.json".into(),
+            Err(anyhow!("inner").context("outer")),
+        );
+
+        assert_eq!(ok.unwrap(), Some(1));
+        assert_eq!(skipped.unwrap(), None);
+        assert_eq!(
+            skipped_inputs.into_sorted_vec(),
+            vec![SkippedInput {
+                kind: InputKind::Program,
+                path: "b.json".into(),
+                reason: "outer: inner".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_duplicates_are_returned_once() {
+        let skipped_inputs = SkippedInputs::new(true);
+
+        for _ in 0..2 {
+            let _: Result<Option<()>> = skipped_inputs.skip_on_error(
+                InputKind::Program,
+                "a.json".into(),
+                Err(anyhow!("missing")),
+            );
+        }
+
+        assert_eq!(skipped_inputs.into_sorted_vec().len(), 1);
+    }
+}
@@ -4,12 +4,25 @@ use clap::Parser;
 /// Arguments accepted by the `clean` subcommand.
 #[derive(Parser, Debug)]
 pub struct CleanArgs {
-    /// Root directory to search for files to clean.
-    /// From this directory, all subdirectories are searched recursively.
+    /// Root directory to search for files to clean. It is never deleted itself.
+    /// From this directory, all subdirectories are searched recursively, except for `.git` and `target`.
     #[arg(short, long, default_value = ".")]
     pub root_dir: Utf8PathBuf,
 
-    /// File name of a file to clean. It should also include the extension.
+    /// Name or glob pattern of the files to clean, in the `.gitignore` format, e.g. `*.lcov`.
+    /// Patterns containing a `/` are anchored at the root directory, e.g. `reports/coverage.lcov` matches only
+    /// that file, while names match at any depth. Patterns matching every file, like `*`, are rejected.
+    /// Can be specified multiple times.
     #[arg(short, long, default_value = "coverage.lcov")]
-    pub files_to_delete: Utf8PathBuf,
+    pub files_to_delete: Vec<String>,
+
+    /// Name or glob pattern of the directories to clean together with their contents, in the `.gitignore` format,
+    /// e.g. `snfoundry_trace` or the directory of the HTML report generated by `genhtml`.
+    /// Patterns matching `.git` or `target` are rejected. Can be specified multiple times.
+    #[arg(short, long)]
+    pub dirs_to_delete: Vec<String>,
+
+    /// List the files and directories that would be deleted without deleting them.
+    #[arg(long)]
+    pub dry_run: bool,
 }
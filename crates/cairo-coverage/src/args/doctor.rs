@@ -0,0 +1,11 @@
+use camino::Utf8PathBuf;
+use clap::Parser;
+
+/// Arguments accepted by the `doctor` subcommand.
+#[derive(Parser, Debug)]
+pub struct DoctorArgs {
+    /// Directory with the built Sierra programs and contract classes to check.
+    /// If not provided, the directory of the active profile in the target directory is used, e.g. `target/dev`.
+    #[arg(long)]
+    pub artifacts_dir: Option<Utf8PathBuf>,
+}
@@ -0,0 +1,31 @@
+use crate::args::run::InputArgs;
+use anyhow::{Context, Result, ensure};
+use cairo_coverage_core::args::SourceLine;
+use clap::Parser;
+
+/// Arguments accepted by the `explain` subcommand.
+#[derive(Parser, Debug)]
+pub struct ExplainArgs {
+    /// Source line to explain in the `FILE:LINE` format, e.g. `src/lib.cairo:12`.
+    /// The file is matched against the end of the source file paths.
+    #[arg(value_parser = parse_source_line, value_name = "FILE:LINE")]
+    pub source_line: SourceLine,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+}
+
+fn parse_source_line(source_line: &str) -> Result<SourceLine> {
+    let (file, line) = source_line
+        .rsplit_once(':')
+        .context("source line must be in the FILE:LINE format")?;
+    let line = line.parse().context("line must be a positive number")?;
+
+    ensure!(!file.is_empty(), "source line FILE cannot be empty");
+    ensure!(line > 0, "line must be a positive number");
+
+    Ok(SourceLine {
+        file: file.into(),
+        line,
+    })
+}
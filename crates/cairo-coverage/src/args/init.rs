@@ -0,0 +1,14 @@
+use clap::Parser;
+
+/// Arguments accepted by the `init` subcommand.
+#[derive(Parser, Debug)]
+pub struct InitArgs {
+    /// Name of the Scarb profile to configure for computing the coverage.
+    /// Profiles other than `dev` and `release` are created inheriting from `dev`.
+    #[arg(long, default_value = "coverage")]
+    pub profile: String,
+
+    /// If set, a starter `.cairo-coverage-ignore` file is created in the workspace root.
+    #[arg(long)]
+    pub ignore_file: bool,
+}
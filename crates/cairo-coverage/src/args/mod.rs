@@ -1,8 +1,15 @@
 use crate::args::clean::CleanArgs;
+use crate::args::doctor::DoctorArgs;
+use crate::args::explain::ExplainArgs;
+use crate::args::init::InitArgs;
 use crate::args::run::RunArgs;
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use log::LevelFilter;
 
 pub mod clean;
+pub mod doctor;
+pub mod explain;
+pub mod init;
 pub mod run;
 
 #[derive(Parser, Debug)]
@@ -11,6 +18,48 @@ pub struct CairoCoverageArgs {
     /// Subcommand and its arguments.
     #[command(subcommand)]
     pub command: Command,
+
+    #[command(flatten)]
+    pub verbosity: Verbosity,
+
+    /// Format of the messages printed by `cairo-coverage`.
+    #[arg(long, value_enum, default_value_t, global = true)]
+    pub message_format: MessageFormat,
+}
+
+/// Format of the messages printed by `cairo-coverage`, i.e. everything except the reports.
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum MessageFormat {
+    /// Messages for humans.
+    #[default]
+    Human,
+    /// A JSON object per line, for other tools.
+    Json,
+}
+
+/// Arguments controlling how many diagnostics are printed.
+#[derive(Args, Debug)]
+pub struct Verbosity {
+    /// Print more diagnostics: `-v` for an overview of the processed inputs, `-vv` for details of every program.
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Print only errors and the inputs skipped due to `--keep-going`, without other warnings.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+}
+
+impl Verbosity {
+    /// Most detailed level of the diagnostics to print.
+    pub fn level_filter(&self) -> LevelFilter {
+        match (self.quiet, self.verbose) {
+            (true, _) => LevelFilter::Error,
+            (false, 0) => LevelFilter::Warn,
+            (false, 1) => LevelFilter::Info,
+            (false, 2) => LevelFilter::Debug,
+            (false, _) => LevelFilter::Trace,
+        }
+    }
 }
 
 /// Subcommand and its arguments.
@@ -19,6 +68,15 @@ pub enum Command {
     /// Clean up coverage files.
     Clean(CleanArgs),
 
+    /// Check that the project is set up to compute the coverage and print the fixes for the problems found.
+    Doctor(DoctorArgs),
+
+    /// Explain the coverage of a single source line by listing the Sierra statements mapped to it.
+    Explain(ExplainArgs),
+
+    /// Configure the project for computing the coverage by adding a dedicated profile to `Scarb.toml`.
+    Init(InitArgs),
+
     /// Run `cairo-coverage` tool.
     Run(RunArgs),
 }
@@ -1,25 +1,98 @@
-use anyhow::{Result, ensure};
+use anyhow::{Context, Result, ensure};
+use cairo_coverage_core::args::PathPrefixMap;
+use cairo_coverage_core::output::reporter::Registry;
 use camino::Utf8PathBuf;
-use clap::{Parser, ValueEnum};
+use clap::{Args, Parser, ValueEnum};
+use serde::Deserialize;
 
 /// Arguments accepted by the `run` subcommand.
 #[derive(Parser, Debug)]
 pub struct RunArgs {
-    /// Paths to the .json files with trace data.
-    #[arg(value_parser = parse_trace_file, num_args = 1.., required = true)]
-    pub trace_files: Vec<Utf8PathBuf>,
+    #[command(flatten)]
+    pub input: InputArgs,
 
     /// Path to the output file.
     #[arg(short, long, default_value = "coverage.lcov")]
     pub output_path: Utf8PathBuf,
 
+    /// Format of the generated report: `lcov`, `summary`, `json` or `sierra`, optionally followed by `=PATH`
+    /// to write the report to the file at PATH. Without PATH, the `lcov` report is appended to the output file,
+    /// while the other reports are printed to the standard output. Can be repeated to produce several reports at once.
+    #[arg(value_parser = parse_format, long, value_name = "FORMAT[=PATH]", default_value = "lcov")]
+    pub format: Vec<FormatArg>,
+
+    #[command(flatten)]
+    pub report: ReportArgs,
+
+    /// If set, source files inside the project directory are reported with paths relative to it.
+    #[arg(long)]
+    pub relative_paths: bool,
+
+    /// If set, print how long each stage of the computation took.
+    #[arg(long)]
+    pub timings: bool,
+}
+
+/// Arguments deciding what the reports contain.
+#[derive(Args, Debug)]
+pub struct ReportArgs {
+    /// If set, a separate LCOV report is written for each contract (or test executable) next to the output file,
+    /// e.g. `coverage.HelloStarknet.lcov`. It contains only the code executed through that contract.
+    #[arg(long)]
+    pub split_by_contract: bool,
+
+    /// If set, the hit count of the lines will not be truncated to 1.
+    #[arg(long)]
+    pub no_truncation: bool,
+
+    /// How the hit count of the lines is computed when it is not truncated.
+    /// The `statements` mode depends on the compiler and requires `--unstable`.
+    #[arg(long, value_enum, default_value_t = HitCountMode::LineEntries, requires_if("statements", "unstable"))]
+    pub hit_count_mode: HitCountMode,
+}
+
+/// Arguments describing the analyzed traces and project, shared by the subcommands that process traces.
+#[derive(Args, Debug)]
+pub struct InputArgs {
+    /// Paths to the .json files with trace data. Files compressed with gzip (.json.gz) or zstd (.json.zst) are also accepted.
+    #[arg(value_parser = parse_trace_file, num_args = 1.., required_unless_present = "vm_trace")]
+    pub trace_files: Vec<Utf8PathBuf>,
+
+    /// Binary trace written directly by the Cairo VM (e.g. with `cairo1-run --trace_file`) together with
+    /// the Sierra program that was executed. Useful for programs run outside of `snforge` and `scarb execute`.
+    /// Can be repeated, each trace with its own optional `OFFSET`: the size of the header preceding the program
+    /// in the CASM code executed by the Cairo VM. If not provided, the program is assumed to be wrapped in a header
+    /// that ends the execution, as done by `cairo1-run`. The memory file and programs compiled to CASM are not supported.
+    #[arg(value_parser = parse_vm_trace, long, value_name = "TRACE=PROGRAM[@OFFSET]")]
+    pub vm_trace: Vec<VmTraceArg>,
+
     /// Include additional components in the coverage report.
     #[arg(long, short, num_args = 0.., default_value = "macros", requires = "unstable")]
     pub include: Vec<IncludedComponent>,
 
-    /// If set, the hit count of the lines will not be truncated to 1.
-    #[arg(long, requires = "unstable")]
-    pub no_truncation: bool,
+    /// If set, traces and programs that cannot be processed are skipped instead of aborting the run.
+    /// Skipped inputs are listed together with the reason at the end.
+    #[arg(long)]
+    pub keep_going: bool,
+
+    /// If set, source files that changed after the program was built fail the run instead of being warned about.
+    /// Traces generated before the program was rebuilt are always only warned about, as the program may be unchanged.
+    /// Combined with `--keep-going`, the affected programs are skipped.
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Directory against which relative Sierra paths from the trace files are resolved.
+    /// If not provided or the file is not found there, the directory of the trace file and the current working directory are tried.
+    #[arg(value_parser = parse_sierra_root, long)]
+    pub sierra_root: Option<Utf8PathBuf>,
+
+    /// Rewrite source file paths starting with FROM to start with TO, e.g. `/builds/project=/home/user/project`.
+    /// Useful for reports produced inside containers. Can be repeated, the first matching rule is applied.
+    #[arg(value_parser = parse_path_prefix_map, long, value_name = "FROM=TO")]
+    pub path_prefix_map: Vec<PathPrefixMap>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
 
     /// If set, the unstable features are enabled.
     #[arg(long)]
@@ -30,6 +103,30 @@ pub struct RunArgs {
     pub project_path: Option<Utf8PathBuf>,
 }
 
+/// Arguments deciding which statements are excluded from the report, besides the included components.
+#[derive(Args, Debug)]
+pub struct FilterArgs {
+    /// Preset of unreliable libfuncs, whose statements are excluded from the report.
+    /// Overrides `preset` from the `[tool.cairo-coverage.unreliable-libfuncs]` section of `Scarb.toml`.
+    #[arg(long, value_enum)]
+    pub libfunc_preset: Option<LibfuncPreset>,
+
+    /// Libfunc name or pattern with `*` wildcards, e.g. `felt252_*`, treated as unreliable in addition to the preset.
+    /// Can be repeated.
+    #[arg(long, value_name = "PATTERN")]
+    pub unreliable_libfunc: Vec<String>,
+
+    /// Libfunc name or pattern with `*` wildcards treated as reliable, even if it is in the preset
+    /// or matches `--unreliable-libfunc`. Can be repeated.
+    #[arg(long, value_name = "PATTERN")]
+    pub reliable_libfunc: Vec<String>,
+
+    /// If set, statements detected as non-executable in the compiled program are excluded from the report:
+    /// the ones compiled to no CASM instructions and the ones mapped to many different lines.
+    #[arg(long)]
+    pub exclude_non_executable: bool,
+}
+
 /// Additional components that can be included in the coverage report.
 #[derive(ValueEnum, Debug, Clone, Eq, PartialEq)]
 pub enum IncludedComponent {
@@ -37,19 +134,130 @@ pub enum IncludedComponent {
     Macros,
 }
 
+/// Ways of computing the hit count of the lines.
+#[derive(ValueEnum, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum HitCountMode {
+    /// Number of times the execution entered the line. Stable across Scarb versions.
+    LineEntries,
+    /// Sum of the execution counts of the statements generated for the line. Varies between Scarb versions.
+    Statements,
+}
+
+/// Base sets of unreliable libfuncs.
+#[derive(ValueEnum, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LibfuncPreset {
+    /// Libfuncs like `store_temp` or `drop` that are generated for many lines, including the not executed ones.
+    Default,
+    /// No libfuncs are unreliable, every statement is counted.
+    Strict,
+}
+
+/// Trace written by the Cairo VM and the program that was executed.
+#[derive(Debug, Clone)]
+pub struct VmTraceArg {
+    pub trace: Utf8PathBuf,
+    pub program: Utf8PathBuf,
+    pub program_offset: Option<usize>,
+}
+
+/// Format of a report and the file it is written to.
+#[derive(Debug, Clone)]
+pub struct FormatArg {
+    /// Name of the [`Reporter`](cairo_coverage_core::output::reporter::Reporter) of the format.
+    pub name: String,
+    /// File the report is written to. If not provided, the default destination of the format is used.
+    pub path: Option<Utf8PathBuf>,
+}
+
+/// Extensions of trace files that can be read, including compressed ones.
+const TRACE_FILE_EXTENSIONS: [&str; 3] = [".json", ".json.gz", ".json.zst"];
+
 fn parse_trace_file(path: &str) -> Result<Utf8PathBuf> {
     let trace_file = Utf8PathBuf::from(path);
 
     ensure!(trace_file.exists(), "trace file does not exist");
     ensure!(trace_file.is_file(), "trace file is not a file");
     ensure!(
-        matches!(trace_file.extension(), Some("json")),
-        "trace file must have a JSON extension"
+        TRACE_FILE_EXTENSIONS
+            .iter()
+            .any(|extension| trace_file.as_str().ends_with(extension)),
+        "trace file must have a JSON extension, optionally compressed (.json.gz or .json.zst)"
     );
 
     Ok(trace_file)
 }
 
+fn parse_vm_trace(vm_trace: &str) -> Result<VmTraceArg> {
+    let (trace, program) = vm_trace
+        .split_once('=')
+        .context("vm trace must be in the TRACE=PROGRAM[@OFFSET] format")?;
+    let (program, program_offset) = match program.rsplit_once('@') {
+        Some((program, offset)) => {
+            let offset = offset
+                .parse()
+                .with_context(|| format!("invalid program offset: {offset}"))?;
+            (program, Some(offset))
+        }
+        None => (program, None),
+    };
+    let (trace, program) = (Utf8PathBuf::from(trace), Utf8PathBuf::from(program));
+
+    ensure!(trace.is_file(), "vm trace file does not exist: {trace}");
+    ensure!(program.is_file(), "program file does not exist: {program}");
+
+    Ok(VmTraceArg {
+        trace,
+        program,
+        program_offset,
+    })
+}
+
+fn parse_format(format: &str) -> Result<FormatArg> {
+    let (name, path) = match format.split_once('=') {
+        Some((name, path)) => (name, Some(Utf8PathBuf::from(path))),
+        None => (format, None),
+    };
+
+    let registry = Registry::default();
+    ensure!(
+        registry.get(name).is_some(),
+        "unknown format: {name}, expected one of: {}",
+        registry.names().collect::<Vec<_>>().join(", ")
+    );
+    ensure!(
+        path.as_ref().is_none_or(|path| !path.as_str().is_empty()),
+        "format PATH cannot be empty"
+    );
+
+    Ok(FormatArg {
+        name: name.to_string(),
+        path,
+    })
+}
+
+fn parse_sierra_root(path: &str) -> Result<Utf8PathBuf> {
+    let sierra_root = Utf8PathBuf::from(path);
+
+    ensure!(sierra_root.exists(), "sierra root does not exist");
+    ensure!(sierra_root.is_dir(), "sierra root is not a directory");
+
+    Ok(sierra_root)
+}
+
+fn parse_path_prefix_map(rule: &str) -> Result<PathPrefixMap> {
+    let (from, to) = rule
+        .split_once('=')
+        .context("path prefix map must be in the FROM=TO format")?;
+
+    ensure!(!from.is_empty(), "path prefix map FROM cannot be empty");
+
+    Ok(PathPrefixMap {
+        from: from.into(),
+        to: to.into(),
+    })
+}
+
 fn parse_project_path(path: &str) -> Result<Utf8PathBuf> {
     let project_path = Utf8PathBuf::from(path);
 
@@ -1,48 +1,181 @@
 use crate::args::clean::CleanArgs;
 use crate::ui;
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, ensure};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use std::fs;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// Directories that are never searched nor deleted, as they contain no files generated by the coverage tools.
+const SKIPPED_DIRS: [&str; 2] = [".git", "target"];
+
+/// Files of every project that patterns of the files to delete cannot match, which rejects catch-all patterns like `*`.
+const PROTECTED_FILES: [&str; 2] = ["Scarb.toml", "src/lib.cairo"];
+
+/// File or directory found by the `clean` command.
+#[derive(Debug, Eq, PartialEq)]
+enum Target {
+    File(PathBuf),
+    Dir(PathBuf),
+}
+
 /// Run the `cairo-coverage clean` command with [`CleanArgs`].
-/// This command deletes all files with the name specified by `files_to_delete` in the `root_dir` and all its subdirectories.
+/// This command deletes all files and directories matching `files_to_delete` and `dirs_to_delete`
+/// in the `root_dir` and its subdirectories, and prints how much space was freed.
+/// With `dry_run` the matching files and directories are only listed.
 pub fn run(
     CleanArgs {
         root_dir,
         files_to_delete,
+        dirs_to_delete,
+        dry_run,
     }: CleanArgs,
 ) -> Result<()> {
-    let target_file_name = files_to_delete
-        .file_name()
-        .context("failed to obtain the file name from `--files-to-delete`")?;
-
-    WalkDir::new(root_dir)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|e| e.file_type().is_file())
-        .try_for_each(|entry| -> Result<()> {
-            let path = entry.path();
-
-            if let Some(file_name) = path.file_name()
-                && file_name == target_file_name
-            {
-                let path_display = path.display();
-                ui::msg(format!("deleting file: {path_display}"));
-                fs::remove_file(path)
-                    .with_context(|| format!("failed to delete file: {path_display}"))?;
+    let file_matcher = build_matcher(&root_dir, &files_to_delete)?;
+    let dir_matcher = build_matcher(&root_dir, &dirs_to_delete)?;
+    for protected_file in PROTECTED_FILES {
+        ensure!(
+            !file_matcher
+                .matched(root_dir.join(protected_file), false)
+                .is_ignore(),
+            "files to delete cannot match `{protected_file}`, patterns matching every file are rejected"
+        );
+    }
+    for skipped_dir in SKIPPED_DIRS {
+        ensure!(
+            !dir_matcher
+                .matched(root_dir.join(skipped_dir), true)
+                .is_ignore(),
+            "directories to delete cannot match `{skipped_dir}`, which is never cleaned"
+        );
+    }
+
+    let targets = find_targets(root_dir.as_std_path(), &file_matcher, &dir_matcher);
+
+    let mut file_count = 0;
+    let mut dir_count = 0;
+    let mut freed_bytes = 0;
+    for target in targets {
+        let (kind, path) = match &target {
+            Target::File(path) => ("file", path),
+            Target::Dir(path) => ("directory", path),
+        };
+        let path_display = path.display();
+        let size = size(&target);
+
+        if dry_run {
+            ui::msg(format!("would delete {kind}: {path_display}"));
+        } else {
+            ui::msg(format!("deleting {kind}: {path_display}"));
+            match &target {
+                Target::File(path) => fs::remove_file(path),
+                Target::Dir(path) => fs::remove_dir_all(path),
             }
+            .with_context(|| format!("failed to delete {kind}: {path_display}"))?;
+        }
 
-            Ok(())
-        })?;
+        match target {
+            Target::File(_) => file_count += 1,
+            Target::Dir(_) => dir_count += 1,
+        }
+        freed_bytes += size;
+    }
 
-    ui::msg("cleanup complete");
+    let freed = format_size(freed_bytes);
+    if dry_run {
+        ui::msg(format!(
+            "would delete {file_count} file(s) and {dir_count} directory(ies), freeing {freed}"
+        ));
+    } else {
+        ui::msg(format!(
+            "cleanup complete, deleted {file_count} file(s) and {dir_count} directory(ies), freed {freed}"
+        ));
+    }
     Ok(())
 }
 
+/// Build a matcher of the names or glob patterns in the `.gitignore` format.
+/// Patterns containing a `/` other than a trailing one are anchored at the `root_dir`, the others match at any depth.
+/// The `.gitignore` files themselves are not read, as the generated files are usually ignored by git.
+fn build_matcher(root_dir: impl AsRef<Path>, patterns: &[String]) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(root_dir);
+    for pattern in patterns {
+        builder
+            .add_line(None, pattern)
+            .with_context(|| format!("invalid pattern: {pattern}"))?;
+    }
+    builder.build().context("failed to build the patterns")
+}
+
+/// Walk the `root_dir` and collect the files and directories to delete, sorted by path.
+/// Matching directories are not searched any further. The `root_dir` and [`SKIPPED_DIRS`] are never matched.
+fn find_targets(root_dir: &Path, file_matcher: &Gitignore, dir_matcher: &Gitignore) -> Vec<Target> {
+    let mut targets = Vec::new();
+    let mut entries = WalkDir::new(root_dir).sort_by_file_name().into_iter();
+    while let Some(entry) = entries.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let path = entry.path();
+
+        if entry.file_type().is_dir() {
+            if entry.depth() == 0 {
+                continue;
+            }
+            if entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| SKIPPED_DIRS.contains(&name))
+            {
+                entries.skip_current_dir();
+            } else if dir_matcher.matched(path, true).is_ignore() {
+                targets.push(Target::Dir(path.to_path_buf()));
+                entries.skip_current_dir();
+            }
+        } else if entry.file_type().is_file() && file_matcher.matched(path, false).is_ignore() {
+            targets.push(Target::File(path.to_path_buf()));
+        }
+    }
+    targets
+}
+
+/// Returns the size of the file or the total size of the files in the directory, in bytes.
+fn size(target: &Target) -> u64 {
+    match target {
+        Target::File(path) => fs::metadata(path).map_or(0, |metadata| metadata.len()),
+        Target::Dir(path) => WalkDir::new(path)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.metadata().ok())
+            .filter(std::fs::Metadata::is_file)
+            .map(|metadata| metadata.len())
+            .sum(),
+    }
+}
+
+/// Format the size in bytes with a binary unit, e.g. `1.50 KiB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    #[expect(clippy::cast_precision_loss)] // Only used for display.
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{size:.2} {unit}")
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::clean;
-    use super::CleanArgs;
+    use super::{CleanArgs, format_size};
     use assert_fs::TempDir;
     use assert_fs::fixture::PathChild;
     use cairo_coverage_test_utils::{CreateFile, Utf8PathBufConversion};
@@ -61,7 +194,9 @@ mod tests {
 
         let clean_args = CleanArgs {
             root_dir: temp_dir.to_utf8_path_buf(),
-            files_to_delete: "coverage.lcov".into(),
+            files_to_delete: vec!["coverage.lcov".into()],
+            dirs_to_delete: vec![],
+            dry_run: false,
         };
 
         clean::run(clean_args).unwrap();
@@ -82,7 +217,9 @@ mod tests {
 
         let clean_args = CleanArgs {
             root_dir: temp_dir.to_utf8_path_buf(),
-            files_to_delete: "nonexistent_file.txt".into(),
+            files_to_delete: vec!["nonexistent_file.txt".into()],
+            dirs_to_delete: vec![],
+            dry_run: false,
         };
 
         clean::run(clean_args).unwrap();
@@ -97,11 +234,127 @@ mod tests {
 
         let clean_args = CleanArgs {
             root_dir: temp_dir.to_utf8_path_buf(),
-            files_to_delete: "coverage.lcov".into(),
+            files_to_delete: vec!["coverage.lcov".into()],
+            dirs_to_delete: vec![],
+            dry_run: false,
         };
 
         clean::run(clean_args).unwrap();
 
         // No assertion needed—just ensuring no panics or errors occur
     }
+
+    #[test]
+    fn test_clean_removes_matching_files_and_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let lcov_file = temp_dir.create_file("unit.lcov");
+        let trace_file = temp_dir.child("snfoundry_trace").create_file("trace.json");
+        let html_file = temp_dir.child("coverage_html").create_file("index.html");
+        let target_file = temp_dir.child("target").create_file("coverage.lcov");
+        let git_file = temp_dir.child(".git").create_file("coverage.lcov");
+        let non_target_file = temp_dir.create_file("keep_this.txt");
+
+        let clean_args = CleanArgs {
+            root_dir: temp_dir.to_utf8_path_buf(),
+            files_to_delete: vec!["*.lcov".into()],
+            dirs_to_delete: vec!["snfoundry_trace".into(), "coverage_html".into()],
+            dry_run: false,
+        };
+
+        clean::run(clean_args).unwrap();
+
+        assert!(!lcov_file.exists());
+        assert!(!trace_file.exists());
+        assert!(!temp_dir.child("snfoundry_trace").exists());
+        assert!(!html_file.exists());
+        assert!(target_file.exists());
+        assert!(git_file.exists());
+        assert!(non_target_file.exists());
+    }
+
+    #[test]
+    fn test_clean_rejects_patterns_matching_skipped_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let target_file = temp_dir.child("target").create_file("coverage.lcov");
+
+        for pattern in ["target", "*", ".git/"] {
+            let clean_args = CleanArgs {
+                root_dir: temp_dir.to_utf8_path_buf(),
+                files_to_delete: vec![],
+                dirs_to_delete: vec![pattern.into()],
+                dry_run: false,
+            };
+
+            assert!(clean::run(clean_args).is_err(), "{pattern}");
+        }
+        assert!(target_file.exists());
+    }
+
+    #[test]
+    fn test_clean_rejects_catch_all_file_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let manifest = temp_dir.create_file("Scarb.toml");
+
+        for pattern in ["*", "**", "/*", "*.toml"] {
+            let clean_args = CleanArgs {
+                root_dir: temp_dir.to_utf8_path_buf(),
+                files_to_delete: vec![pattern.into()],
+                dirs_to_delete: vec![],
+                dry_run: false,
+            };
+
+            assert!(clean::run(clean_args).is_err(), "{pattern}");
+        }
+        assert!(manifest.exists());
+    }
+
+    #[test]
+    fn test_clean_anchors_patterns_with_slash() {
+        let temp_dir = TempDir::new().unwrap();
+        let anchored_file = temp_dir.child("reports").create_file("coverage.lcov");
+        let nested_file = temp_dir
+            .child("nested")
+            .child("reports")
+            .create_file("coverage.lcov");
+
+        let clean_args = CleanArgs {
+            root_dir: temp_dir.to_utf8_path_buf(),
+            files_to_delete: vec!["reports/coverage.lcov".into()],
+            dirs_to_delete: vec![],
+            dry_run: false,
+        };
+
+        clean::run(clean_args).unwrap();
+
+        assert!(!anchored_file.exists());
+        assert!(nested_file.exists());
+    }
+
+    #[test]
+    fn test_clean_dry_run_keeps_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.create_file("coverage.lcov");
+        let trace_file = temp_dir.child("snfoundry_trace").create_file("trace.json");
+
+        let clean_args = CleanArgs {
+            root_dir: temp_dir.to_utf8_path_buf(),
+            files_to_delete: vec!["coverage.lcov".into()],
+            dirs_to_delete: vec!["snfoundry_trace".into()],
+            dry_run: true,
+        };
+
+        clean::run(clean_args).unwrap();
+
+        assert!(file.exists());
+        assert!(trace_file.exists());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.50 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.00 MiB");
+    }
 }
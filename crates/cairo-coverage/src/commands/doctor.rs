@@ -0,0 +1,255 @@
+use crate::args::doctor::DoctorArgs;
+use crate::commands::run::{MACRO_EXCLUSION_SCARB_VERSION, scarb_metadata};
+use crate::ui;
+use anyhow::{Context, Result, ensure};
+use cairo_coverage_core::error::Error;
+use cairo_coverage_core::setup::{self, CompilerSetting, REQUIRED_COMPILER_SETTINGS};
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use scarb_metadata::Metadata;
+use semver::Version;
+use serde_json::Value;
+use std::fs;
+
+/// Oldest Scarb version producing the debug information required to compute the coverage.
+const MINIMAL_SCARB_VERSION: Version = Version::new(2, 8, 0);
+
+/// Problem with the project setup found by a check, together with the way to fix it.
+struct Problem {
+    description: String,
+    fix: String,
+}
+
+/// Everything found by a single check: the [`Problem`]s and notes about features not available in this setup.
+struct Findings {
+    problems: Vec<Problem>,
+    notes: Vec<String>,
+}
+
+impl From<Vec<Problem>> for Findings {
+    fn from(problems: Vec<Problem>) -> Self {
+        Self {
+            problems,
+            notes: Vec::new(),
+        }
+    }
+}
+
+/// Run the `cairo-coverage doctor` command with [`DoctorArgs`].
+/// This command checks the Scarb version, the compiler configuration of the active profile
+/// and the built artifacts, printing the fixes for all problems found.
+pub fn run(DoctorArgs { artifacts_dir }: DoctorArgs) -> Result<()> {
+    let metadata = scarb_metadata()?;
+    let artifacts_dir = artifacts_dir.unwrap_or_else(|| default_artifacts_dir(&metadata));
+
+    let checks = [
+        ("scarb version", check_scarb_version(&metadata)),
+        (
+            "compiler configuration",
+            check_compiler_config(&metadata).into(),
+        ),
+        ("built artifacts", check_artifacts(&artifacts_dir)?.into()),
+    ];
+
+    let mut problem_count = 0;
+    for (name, Findings { problems, notes }) in checks {
+        if problems.is_empty() {
+            ui::msg(format!("{name}: ok"));
+        }
+        for note in notes {
+            ui::diagnostic(format!("{name}: note: {note}"));
+        }
+        for Problem { description, fix } in problems {
+            problem_count += 1;
+            // The fix is printed with the problem, so they are not separated with `--quiet` or JSON messages.
+            ui::warn_always(format!("{name}: {description}\nfix: {fix}"));
+        }
+    }
+
+    ensure!(
+        problem_count == 0,
+        "found {problem_count} problem(s) with the project setup"
+    );
+    Ok(())
+}
+
+/// Check that the Scarb version produces the required debug information.
+/// Features that are not available in this version are returned as notes.
+fn check_scarb_version(metadata: &Metadata) -> Findings {
+    let version = &metadata.app_version_info.version;
+    if *version < MINIMAL_SCARB_VERSION {
+        return vec![Problem {
+            description: format!(
+                "scarb {version} is not supported, at least {MINIMAL_SCARB_VERSION} is required"
+            ),
+            fix: "install a newer version of Scarb, e.g. with `starkup`".to_string(),
+        }]
+        .into();
+    }
+    let mut notes = Vec::new();
+    if *version < MACRO_EXCLUSION_SCARB_VERSION {
+        notes.push(format!(
+            "excluding macros from the report requires scarb {MACRO_EXCLUSION_SCARB_VERSION} or newer, found {version}"
+        ));
+    }
+    Findings {
+        problems: Vec::new(),
+        notes,
+    }
+}
+
+/// Check that all workspace members are compiled with the [`REQUIRED_COMPILER_SETTINGS`] in the active profile.
+fn check_compiler_config(metadata: &Metadata) -> Vec<Problem> {
+    let missing_settings = metadata
+        .compilation_units
+        .iter()
+        .filter(|unit| metadata.workspace.members.contains(&unit.package))
+        .flat_map(|unit| missing_compiler_settings(&unit.compiler_config))
+        .unique_by(|setting| setting.name)
+        .collect::<Vec<_>>();
+
+    if missing_settings.is_empty() {
+        return Vec::new();
+    }
+
+    let profile = &metadata.current_profile;
+    vec![Problem {
+        description: format!(
+            "the `{profile}` profile does not set {}",
+            missing_settings
+                .iter()
+                .map(|setting| format!("`{} = {}`", setting.name, setting.value))
+                .join(", ")
+        ),
+        fix: format!(
+            "add the following to {}:\n\n{}",
+            metadata.workspace.manifest_path,
+            setup::profile_toml(profile, missing_settings)
+        ),
+    }]
+}
+
+/// Returns the [`REQUIRED_COMPILER_SETTINGS`] that are not set in the compiler config from `scarb metadata`.
+fn missing_compiler_settings(compiler_config: &Value) -> Vec<&'static CompilerSetting> {
+    REQUIRED_COMPILER_SETTINGS
+        .iter()
+        .filter(|setting| {
+            let expected = serde_json::from_str::<Value>(setting.value)
+                .unwrap_or_else(|_| unreachable!("required values are valid JSON"));
+            // Scarb reports the config with snake case keys.
+            compiler_config
+                .get(setting.name.replace('-', "_"))
+                .or_else(|| compiler_config.get(setting.name))
+                != Some(&expected)
+        })
+        .collect()
+}
+
+/// Check that every program in the artifacts directory has the debug information required to compute the coverage.
+fn check_artifacts(artifacts_dir: &Utf8Path) -> Result<Vec<Problem>> {
+    if !artifacts_dir.is_dir() {
+        return Ok(vec![no_artifacts_problem(artifacts_dir)]);
+    }
+
+    let artifacts = fs::read_dir(artifacts_dir)
+        .context(format!("failed to read directory: {artifacts_dir}"))?
+        .map(|entry| -> Result<Utf8PathBuf> {
+            let path = entry?.path();
+            Utf8PathBuf::from_path_buf(path)
+                .map_err(|path| anyhow::anyhow!("path is not valid UTF-8: {}", path.display()))
+        })
+        .filter_ok(|path| is_program(path))
+        .collect::<Result<Vec<_>>>()?;
+
+    if artifacts.is_empty() {
+        return Ok(vec![no_artifacts_problem(artifacts_dir)]);
+    }
+
+    Ok(artifacts
+        .into_iter()
+        .sorted()
+        .filter_map(|path| {
+            let error = cairo_coverage_core::check_program(&path).err()?;
+            let fix = match error {
+                Error::MissingDebugInfo { .. } => {
+                    "rebuild the project after fixing the compiler configuration"
+                }
+                _ => "rebuild the project, e.g. with `snforge test`",
+            };
+            Some(Problem {
+                description: format!("{path} cannot be used: {}", error_reason(&error)),
+                fix: fix.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Returns the reason of the error without the path of the program, which is already included in the description.
+fn error_reason(error: &Error) -> String {
+    match error {
+        Error::MissingDebugInfo { reason, .. } | Error::InvalidProgram { reason, .. } => {
+            reason.clone()
+        }
+        _ => error.to_string(),
+    }
+}
+
+fn no_artifacts_problem(artifacts_dir: &Utf8Path) -> Problem {
+    Problem {
+        description: format!("no Sierra programs or contract classes found in {artifacts_dir}"),
+        fix: "build the project, e.g. with `snforge test`, or pass the directory with `--artifacts-dir`"
+            .to_string(),
+    }
+}
+
+/// Returns `true` if the file is a Sierra program or a contract class built by Scarb.
+fn is_program(path: &Utf8Path) -> bool {
+    path.file_name().is_some_and(|file_name| {
+        file_name.ends_with(".sierra.json") || file_name.ends_with(".contract_class.json")
+    })
+}
+
+/// Directory of the active profile in the target directory, where Scarb puts the built artifacts.
+fn default_artifacts_dir(metadata: &Metadata) -> Utf8PathBuf {
+    metadata
+        .target_dir
+        .clone()
+        .unwrap_or_else(|| metadata.workspace.root.join("target"))
+        .join(&metadata.current_profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use cairo_coverage_test_utils::{CreateFile, Utf8PathBufConversion};
+    use serde_json::json;
+
+    #[test]
+    fn test_missing_compiler_settings() {
+        let compiler_config = json!({
+            "unstable_add_statements_functions_debug_info": true,
+            "unstable_add_statements_code_locations_debug_info": false,
+            "inlining_strategy": "default",
+        });
+
+        assert_eq!(
+            missing_compiler_settings(&compiler_config),
+            [
+                &REQUIRED_COMPILER_SETTINGS[1],
+                &REQUIRED_COMPILER_SETTINGS[2]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_artifacts_without_programs() {
+        let temp_dir = TempDir::new().unwrap();
+        temp_dir.create_file("package.starknet_artifacts.json");
+
+        let problems = check_artifacts(&temp_dir.to_utf8_path_buf()).unwrap();
+
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].description.starts_with("no Sierra programs"));
+    }
+}
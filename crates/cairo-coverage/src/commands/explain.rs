@@ -0,0 +1,26 @@
+use crate::args::explain::ExplainArgs;
+use crate::commands::run::{Inputs, inputs, print_skipped_inputs};
+use crate::ui;
+use anyhow::Result;
+use cairo_coverage_core::report::Report;
+
+/// Run the `cairo-coverage explain` command with [`ExplainArgs`].
+/// The explanation of the source line is printed to the standard output.
+pub fn run(ExplainArgs { source_line, input }: ExplainArgs) -> Result<()> {
+    let Inputs {
+        trace_files,
+        project_path,
+        options,
+    } = inputs(input)?;
+
+    let Report {
+        output,
+        skipped_inputs,
+        ..
+    } = cairo_coverage_core::explain(trace_files, project_path, &source_line, options)?;
+
+    ui::msg(output.trim_end());
+    print_skipped_inputs(&skipped_inputs);
+
+    Ok(())
+}
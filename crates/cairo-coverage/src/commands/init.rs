@@ -0,0 +1,178 @@
+use crate::args::init::InitArgs;
+use crate::commands::run::scarb_metadata;
+use crate::ui;
+use anyhow::{Context, Result};
+use cairo_coverage_core::setup::{CAIRO_COVERAGE_IGNORE, REQUIRED_COMPILER_SETTINGS};
+use indoc::indoc;
+use std::fs;
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+/// Profiles defined by Scarb, which cannot inherit from other profiles.
+const BUILTIN_PROFILES: [&str; 2] = ["dev", "release"];
+
+/// Content of the [`CAIRO_COVERAGE_IGNORE`] file created by `init`.
+const STARTER_CAIRO_COVERAGE_IGNORE: &str = indoc! {
+    "
+    # Paths excluded from the coverage report, one per line, in the `.gitignore` format.
+    # For example, to ignore everything in the `utils` directory:
+    # */utils/*
+    "
+};
+
+/// Run the `cairo-coverage init` command with [`InitArgs`].
+/// This command adds the profile with the debug information required to compute the coverage
+/// to the `Scarb.toml` of the workspace, preserving its formatting.
+pub fn run(
+    InitArgs {
+        profile,
+        ignore_file,
+    }: InitArgs,
+) -> Result<()> {
+    let metadata = scarb_metadata()?;
+    let manifest_path = &metadata.workspace.manifest_path;
+
+    let mut manifest = fs::read_to_string(manifest_path)
+        .context(format!("failed to read file at path: {manifest_path}"))?
+        .parse::<DocumentMut>()
+        .context(format!("failed to parse file at path: {manifest_path}"))?;
+
+    if configure_profile(&mut manifest, &profile)? {
+        fs::write(manifest_path, manifest.to_string())
+            .context(format!("failed to write file at path: {manifest_path}"))?;
+        ui::msg(format!(
+            "configured the `{profile}` profile in {manifest_path}"
+        ));
+    } else {
+        ui::msg(format!(
+            "the `{profile}` profile in {manifest_path} is already configured"
+        ));
+    }
+
+    if ignore_file {
+        let ignore_path = metadata.workspace.root.join(CAIRO_COVERAGE_IGNORE);
+        if ignore_path.exists() {
+            ui::warn(format!(
+                "{ignore_path} already exists, leaving it unchanged"
+            ));
+        } else {
+            fs::write(&ignore_path, STARTER_CAIRO_COVERAGE_IGNORE)
+                .context(format!("failed to write file at path: {ignore_path}"))?;
+            ui::msg(format!("created {ignore_path}"));
+        }
+    }
+
+    ui::msg(format!(
+        "run `snforge test --coverage --profile {profile}` to generate the coverage report"
+    ));
+    Ok(())
+}
+
+/// Set the [`REQUIRED_COMPILER_SETTINGS`] in the `[profile.<profile>.cairo]` section of the manifest.
+/// A missing custom profile is created inheriting from `dev`.
+/// Returns `true` if the manifest was changed.
+fn configure_profile(manifest: &mut DocumentMut, profile: &str) -> Result<bool> {
+    let profiles = table_mut(manifest.as_table_mut(), "profile")?;
+    let is_new = !profiles.contains_key(profile);
+    let profile_table = table_mut(profiles, profile)?;
+    if is_new && !BUILTIN_PROFILES.contains(&profile) {
+        profile_table.insert("inherits", toml_edit::value("dev"));
+    }
+
+    let cairo = table_mut(profile_table, "cairo")?;
+    let mut changed = is_new;
+    for setting in &REQUIRED_COMPILER_SETTINGS {
+        let expected = setting
+            .value
+            .parse::<Value>()
+            .unwrap_or_else(|_| unreachable!("required values are valid TOML"));
+        let current = cairo.get(setting.name).and_then(Item::as_value);
+        if !current.is_some_and(|current| is_same_value(current, &expected)) {
+            cairo.insert(setting.name, Item::Value(expected));
+            changed = true;
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Returns the table under the key, inserting an implicit one if it is missing.
+/// Implicit tables have no header of their own, e.g. `[profile]` is not written for `[profile.coverage]`.
+fn table_mut<'a>(parent: &'a mut Table, key: &str) -> Result<&'a mut Table> {
+    parent
+        .entry(key)
+        .or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        })
+        .as_table_mut()
+        .context(format!("`{key}` in Scarb.toml is not a table"))
+}
+
+/// Compare the values ignoring their formatting.
+fn is_same_value(a: &Value, b: &Value) -> bool {
+    a.as_bool() == b.as_bool() && a.as_str() == b.as_str() && a.as_integer() == b.as_integer()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configure(manifest: &str, profile: &str) -> (bool, String) {
+        let mut manifest = manifest.parse::<DocumentMut>().unwrap();
+        let changed = configure_profile(&mut manifest, profile).unwrap();
+        (changed, manifest.to_string())
+    }
+
+    #[test]
+    fn test_configure_new_profile_preserves_formatting() {
+        let manifest = indoc! {r#"
+            [package]
+            name = "hello"  # the package name
+            version = "0.1.0"
+        "#};
+
+        let (changed, manifest) = configure(manifest, "coverage");
+
+        assert!(changed);
+        assert_eq!(
+            manifest,
+            indoc! {r#"
+                [package]
+                name = "hello"  # the package name
+                version = "0.1.0"
+
+                [profile.coverage]
+                inherits = "dev"
+
+                [profile.coverage.cairo]
+                unstable-add-statements-functions-debug-info = true
+                unstable-add-statements-code-locations-debug-info = true
+                inlining-strategy = "avoid"
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_configure_existing_profile_sets_missing_entries() {
+        let manifest = indoc! {r#"
+            [profile.dev.cairo]
+            unstable-add-statements-functions-debug-info = true
+            inlining-strategy = "default"
+        "#};
+
+        let (changed, manifest) = configure(manifest, "dev");
+
+        assert!(changed);
+        assert_eq!(
+            manifest,
+            indoc! {r#"
+                [profile.dev.cairo]
+                unstable-add-statements-functions-debug-info = true
+                inlining-strategy = "avoid"
+                unstable-add-statements-code-locations-debug-info = true
+            "#}
+        );
+        assert!(!configure(&manifest, "dev").0);
+    }
+}
@@ -1,4 +1,7 @@
 mod clean;
+mod doctor;
+mod explain;
+mod init;
 mod run;
 
 use crate::args::Command;
@@ -8,6 +11,9 @@ use anyhow::Result;
 pub fn run(command: Command) -> Result<()> {
     match command {
         Command::Clean(args) => clean::run(args),
+        Command::Doctor(args) => doctor::run(args),
+        Command::Explain(args) => explain::run(args),
+        Command::Init(args) => init::run(args),
         Command::Run(args) => run::run(args),
     }
 }
@@ -1,59 +1,433 @@
-use crate::args::run::{IncludedComponent, RunArgs};
-use anyhow::{Context, Result, ensure};
-use cairo_coverage_core::args::{IncludedComponent as CoreIncludedComponent, RunOptions};
-use scarb_metadata::{Metadata, MetadataCommand};
+use crate::args::run::{
+    FilterArgs, FormatArg, HitCountMode, IncludedComponent, InputArgs, LibfuncPreset, ReportArgs,
+    RunArgs, VmTraceArg,
+};
+use crate::config::{self, Config};
+use crate::ui;
+use anyhow::{Context, Result, bail, ensure};
+use cairo_coverage_core::args::{
+    HitCountMode as CoreHitCountMode, IncludedComponent as CoreIncludedComponent, LibfuncPolicy,
+    LibfuncPreset as CoreLibfuncPreset, RunOptions, VmTrace,
+};
+use cairo_coverage_core::model::Coverage;
+use cairo_coverage_core::output::lcov;
+use cairo_coverage_core::output::reporter::{DefaultDestination, Registry, Reporter};
+use cairo_coverage_core::progress::{ProgressCallback, ProgressEvent, Stage};
+use cairo_coverage_core::report::SkippedInput;
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use scarb_metadata::{Metadata, MetadataCommand, PackageMetadata};
 use semver::Version;
+use std::collections::BTreeMap;
 use std::fs::OpenOptions;
-use std::io::Write;
+use std::io;
+use std::io::{BufWriter, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// First Scarb version that marks the code generated by macros, so it can be excluded from the report.
+pub const MACRO_EXCLUSION_SCARB_VERSION: Version = Version::new(2, 11, 0);
 
 /// Run the `cairo-coverage run` command with [`RunArgs`].
-/// This is done by calling the [`cairo_coverage_core`] crate and writing the output to the `output_path`.
+/// This is done by computing the [`Coverage`] with the [`cairo_coverage_core`] crate once
+/// and writing a report for each requested format with the matching [`Reporter`].
 pub fn run(
     RunArgs {
-        include,
-        project_path,
+        input,
         output_path,
-        trace_files,
-        no_truncation,
-        unstable: _,
+        format,
+        report:
+            ReportArgs {
+                split_by_contract,
+                no_truncation,
+                hit_count_mode,
+            },
+        relative_paths,
+        timings,
     }: RunArgs,
 ) -> Result<()> {
+    let Inputs {
+        trace_files,
+        project_path,
+        mut options,
+    } = inputs(input)?;
+
+    let registry = Registry::default();
+    let reporters = format
+        .iter()
+        .map(|FormatArg { name, path }| {
+            let reporter = registry
+                .get(name)
+                .unwrap_or_else(|| unreachable!("format names are validated when parsing"));
+            (
+                reporter,
+                destination(reporter, path.as_deref(), &output_path),
+            )
+        })
+        .collect::<Vec<_>>();
+    ensure_distinct_destinations(&reporters)?;
+    if ui::is_json() {
+        ensure_no_stdout_reports(&reporters)?;
+    }
+
+    let stage_timings = timings.then(StageTimings::default);
+
+    options.report.no_truncation = no_truncation;
+    options.report.hit_count_mode = hit_count_mode.into();
+    options.report.sierra_statements = reporters
+        .iter()
+        .any(|(reporter, _)| reporter.requires_statements());
+    options.relative_paths = relative_paths;
+    options.progress_callback = stage_timings.as_ref().map(StageTimings::callback);
+
+    let coverage = cairo_coverage_core::coverage(trace_files, project_path, options)?;
+
+    let formatting_start = Instant::now();
+    for (reporter, destination) in reporters {
+        write_report(reporter, &coverage, destination)?;
+        if let Destination::File { path, .. } = destination {
+            ui::report_written(reporter.name(), path);
+        }
+    }
+
+    if split_by_contract {
+        let name_counts = coverage
+            .programs
+            .iter()
+            .map(|program| &program.name)
+            .counts();
+        for program in &coverage.programs {
+            // Contracts with the same name from different packages are told apart by their artifacts.
+            let name = if name_counts[&program.name] > 1 {
+                &program.artifact_name
+            } else {
+                &program.name
+            };
+            let contract_output_path = contract_output_path(&output_path, name);
+            write_to_file(&contract_output_path, true, |writer| {
+                lcov::write(writer, &program.files)
+            })?;
+            ui::report_written("lcov", &contract_output_path);
+        }
+    }
+
+    print_skipped_inputs(&coverage.skipped_inputs);
+    ui::summary(coverage.files.len(), coverage.line_counts());
+
+    if let Some(stage_timings) = stage_timings {
+        stage_timings.add(Stage::Formatting, formatting_start.elapsed());
+        stage_timings.print();
+    }
+
+    Ok(())
+}
+
+/// Where a single report is written.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Destination<'a> {
+    Stdout,
+    /// File that is overwritten or, if `append` is set, appended to.
+    File {
+        path: &'a Utf8Path,
+        append: bool,
+    },
+}
+
+/// Resolve the [`Destination`] of the report: the file at `path`, which is overwritten, or if not provided,
+/// the [`DefaultDestination`] of the [`Reporter`], where the output file is `output_path`.
+fn destination<'a>(
+    reporter: &dyn Reporter,
+    path: Option<&'a Utf8Path>,
+    output_path: &'a Utf8Path,
+) -> Destination<'a> {
+    match (path, reporter.default_destination()) {
+        (Some(path), _) => Destination::File {
+            path,
+            append: false,
+        },
+        (None, DefaultDestination::Stdout) => Destination::Stdout,
+        // Reports with destinations added in the future are written where `lcov` is, never mixed into stdout.
+        (None, DefaultDestination::OutputFile | _) => Destination::File {
+            path: output_path,
+            append: true,
+        },
+    }
+}
+
+/// Ensure that no report would be written twice to the same destination
+/// and that different reports are not written to the same file.
+fn ensure_distinct_destinations(reporters: &[(&dyn Reporter, Destination)]) -> Result<()> {
+    for [(reporter, destination), (other_reporter, other_destination)] in
+        reporters.iter().array_combinations()
+    {
+        match (destination, other_destination) {
+            (Destination::Stdout, Destination::Stdout) => ensure!(
+                reporter.name() != other_reporter.name(),
+                "format {} is printed to the standard output more than once",
+                reporter.name()
+            ),
+            (
+                Destination::File { path, .. },
+                Destination::File {
+                    path: other_path, ..
+                },
+            ) => {
+                ensure!(
+                    path != other_path,
+                    "formats {} and {} cannot be written to the same file: {path}",
+                    reporter.name(),
+                    other_reporter.name()
+                );
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Ensure that no report is printed to the standard output, where it would be mixed with the JSON messages.
+fn ensure_no_stdout_reports(reporters: &[(&dyn Reporter, Destination)]) -> Result<()> {
+    if let Some((reporter, _)) = reporters
+        .iter()
+        .find(|(_, destination)| *destination == Destination::Stdout)
+    {
+        bail!(
+            "format {name} cannot be printed to the standard output with `--message-format json`, write it to a file with `--format {name}=PATH`",
+            name = reporter.name()
+        );
+    }
+    Ok(())
+}
+
+/// Write the report of the [`Coverage`] with the [`Reporter`] to the [`Destination`].
+fn write_report(
+    reporter: &dyn Reporter,
+    coverage: &Coverage,
+    destination: Destination,
+) -> Result<()> {
+    match destination {
+        Destination::Stdout => reporter
+            .write(coverage, &mut io::stdout().lock())
+            .context("failed to write the report to the standard output"),
+        Destination::File { path, append } => {
+            write_to_file(path, append, |writer| reporter.write(coverage, writer))
+        }
+    }
+}
+
+/// Write to the file at the given path through a buffer, creating the file if it does not exist.
+/// The file is appended to if `append` is set, otherwise it is overwritten.
+fn write_to_file(
+    path: &Utf8Path,
+    append: bool,
+    write: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> Result<()> {
+    let file = OpenOptions::new()
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .create(true)
+        .open(path)
+        .context(format!("failed to open output file at path: {path}"))?;
+
+    let mut writer = BufWriter::new(file);
+    write(&mut writer)
+        .and_then(|()| writer.flush())
+        .context(format!("failed to write to output file at path: {path}"))
+}
+
+/// Total time spent in each [`Stage`], collected from the [`ProgressEvent`]s.
+#[derive(Clone, Default)]
+struct StageTimings(Arc<Mutex<BTreeMap<Stage, Duration>>>);
+
+impl StageTimings {
+    /// Create a [`ProgressCallback`] adding up the durations of the finished stages.
+    fn callback(&self) -> ProgressCallback {
+        let stage_timings = self.clone();
+        ProgressCallback::new(move |event| {
+            if let ProgressEvent::StageFinished { stage, duration } = event {
+                stage_timings.add(stage, duration);
+            }
+        })
+    }
+
+    fn add(&self, stage: Stage, duration: Duration) {
+        *self
+            .0
+            .lock()
+            .unwrap_or_else(|_| unreachable!("no code panics while holding the lock"))
+            .entry(stage)
+            .or_default() += duration;
+    }
+
+    /// Print the time spent in each stage.
+    /// Stages done for each program in parallel are summed up, so they can exceed the wall-clock time.
+    fn print(&self) {
+        let timings = self
+            .0
+            .lock()
+            .unwrap_or_else(|_| unreachable!("no code panics while holding the lock"));
+        ui::diagnostic("timings:");
+        for (stage, duration) in timings.iter() {
+            ui::diagnostic(format!("- {}: {duration:.2?}", stage.name()));
+        }
+    }
+}
+
+/// Traces, project path and [`RunOptions`] resolved from the [`InputArgs`].
+/// Options related to the output are left at their defaults.
+pub struct Inputs {
+    pub trace_files: Vec<Utf8PathBuf>,
+    pub project_path: Utf8PathBuf,
+    pub options: RunOptions,
+}
+
+/// Resolve the [`Inputs`] from the [`InputArgs`], using `scarb metadata` to find the project packages.
+pub fn inputs(
+    InputArgs {
+        trace_files,
+        vm_trace,
+        include,
+        keep_going,
+        strict,
+        sierra_root,
+        path_prefix_map,
+        filter:
+            FilterArgs {
+                libfunc_preset,
+                unreliable_libfunc,
+                reliable_libfunc,
+                exclude_non_executable,
+            },
+        unstable: _,
+        project_path,
+    }: InputArgs,
+) -> Result<Inputs> {
     let metadata = scarb_metadata()?;
+    let Config {
+        unreliable_libfuncs,
+    } = config::load(&metadata)?;
 
     ensure!(
         include.contains(&IncludedComponent::Macros)
-            || metadata.app_version_info.version >= Version::new(2, 11, 0),
-        "excluding macros is only supported for Scarb versions >= 2.11.0"
+            || metadata.app_version_info.version >= MACRO_EXCLUSION_SCARB_VERSION,
+        "excluding macros is only supported for Scarb versions >= {MACRO_EXCLUSION_SCARB_VERSION}"
     );
 
-    let project_path = project_path.unwrap_or(metadata.workspace.root);
+    let PackageRoots {
+        workspace_members,
+        dependencies,
+    } = package_roots(&metadata);
 
-    let options = RunOptions {
-        include: include.into_iter().map(Into::into).collect(),
-        no_truncation,
+    // Workspace members are only considered when the project path is inferred.
+    let (project_path, workspace_member_paths) = match project_path {
+        Some(project_path) => (project_path, Vec::new()),
+        None => (metadata.workspace.root, workspace_members),
     };
 
-    let lcov = cairo_coverage_core::run(trace_files, project_path, options)?;
+    // The options are assigned one by one, as `RunOptions` can gain new fields without a breaking change.
+    let mut options = RunOptions::default();
+    options.filter.include = include.into_iter().map(Into::into).collect();
+    // Options from the command line take precedence over the ones from `Scarb.toml`.
+    options.filter.libfunc_policy = LibfuncPolicy {
+        preset: libfunc_preset
+            .or(unreliable_libfuncs.preset)
+            .map(Into::into)
+            .unwrap_or_default(),
+        unreliable: [unreliable_libfuncs.add, unreliable_libfunc].concat(),
+        reliable: [unreliable_libfuncs.remove, reliable_libfunc].concat(),
+    };
+    options.filter.exclude_non_executable = exclude_non_executable;
+    options.keep_going = keep_going;
+    options.strict = strict;
+    options.sierra_root = sierra_root;
+    options.path_prefix_maps = path_prefix_map;
+    options.workspace_member_paths = workspace_member_paths;
+    options.dependency_paths = dependencies;
+    options.vm_traces = vm_trace
+        .into_iter()
+        .map(
+            |VmTraceArg {
+                 trace,
+                 program,
+                 program_offset,
+             }| VmTrace {
+                trace,
+                program,
+                program_offset,
+            },
+        )
+        .collect();
 
-    OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open(&output_path)
-        .context(format!("failed to open output file at path: {output_path}"))?
-        .write_all(lcov.as_bytes())
-        .context("failed to write to output file")?;
+    Ok(Inputs {
+        trace_files,
+        project_path,
+        options,
+    })
+}
 
-    Ok(())
+/// Path of the report of a single contract, e.g. `coverage.lcov` -> `coverage.HelloStarknet.lcov`.
+/// Characters of the contract name that are not allowed in file names on every platform are replaced with `_`.
+fn contract_output_path(output_path: &Utf8Path, contract_name: &str) -> Utf8PathBuf {
+    let stem = output_path.file_stem().unwrap_or("coverage");
+    let extension = output_path.extension().unwrap_or("lcov");
+    let contract_name: String = contract_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    output_path.with_file_name(format!("{stem}.{contract_name}.{extension}"))
+}
+
+/// Warn about every input skipped due to `--keep-going` together with the reason, even with `--quiet`.
+pub fn print_skipped_inputs(skipped_inputs: &[SkippedInput]) {
+    for SkippedInput {
+        kind, path, reason, ..
+    } in skipped_inputs
+    {
+        ui::warn_always(format!("skipped {kind} {path}: {reason}"));
+    }
 }
 
 /// Run `scarb metadata` command and return the metadata.
-fn scarb_metadata() -> Result<Metadata> {
+pub fn scarb_metadata() -> Result<Metadata> {
     MetadataCommand::new()
         .inherit_stderr()
         .exec()
         .context("could not gather project metadata from Scarb due to previous error")
 }
 
+/// Root directories of the packages from [`Metadata`].
+struct PackageRoots {
+    workspace_members: Vec<Utf8PathBuf>,
+    dependencies: Vec<Utf8PathBuf>,
+}
+
+/// Split the root directories of all packages into workspace members and dependencies.
+fn package_roots(metadata: &Metadata) -> PackageRoots {
+    let (workspace_members, dependencies): (Vec<_>, Vec<_>) = metadata
+        .packages
+        .iter()
+        .partition(|package| metadata.workspace.members.contains(&package.id));
+
+    let roots = |packages: Vec<&PackageMetadata>| {
+        packages
+            .into_iter()
+            .map(|package| package.root.clone())
+            .collect()
+    };
+
+    PackageRoots {
+        workspace_members: roots(workspace_members),
+        dependencies: roots(dependencies),
+    }
+}
+
 impl From<IncludedComponent> for CoreIncludedComponent {
     fn from(component: IncludedComponent) -> Self {
         match component {
@@ -61,3 +435,97 @@ impl From<IncludedComponent> for CoreIncludedComponent {
         }
     }
 }
+
+impl From<HitCountMode> for CoreHitCountMode {
+    fn from(mode: HitCountMode) -> Self {
+        match mode {
+            HitCountMode::LineEntries => CoreHitCountMode::LineEntries,
+            HitCountMode::Statements => CoreHitCountMode::Statements,
+        }
+    }
+}
+
+impl From<LibfuncPreset> for CoreLibfuncPreset {
+    fn from(preset: LibfuncPreset) -> Self {
+        match preset {
+            LibfuncPreset::Default => CoreLibfuncPreset::Default,
+            LibfuncPreset::Strict => CoreLibfuncPreset::Strict,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_destinations_are_rejected() {
+        let registry = Registry::default();
+        let lcov = registry.get("lcov").unwrap();
+        let json = registry.get("json").unwrap();
+        let summary = registry.get("summary").unwrap();
+        let output_path = Utf8Path::new("coverage.lcov");
+        let report = |reporter, path: Option<&'static str>| {
+            (
+                reporter,
+                destination(reporter, path.map(Utf8Path::new), output_path),
+            )
+        };
+
+        assert!(
+            ensure_distinct_destinations(&[
+                report(lcov, None),
+                report(lcov, Some("other.lcov")),
+                report(json, None),
+                report(summary, None),
+            ])
+            .is_ok()
+        );
+        assert!(ensure_distinct_destinations(&[report(lcov, None), report(lcov, None)]).is_err());
+        assert!(
+            ensure_distinct_destinations(&[report(summary, None), report(summary, None)]).is_err()
+        );
+        assert!(
+            ensure_distinct_destinations(&[
+                report(lcov, None),
+                report(json, Some("coverage.lcov"))
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_stdout_reports_are_rejected() {
+        let registry = Registry::default();
+        let lcov = registry.get("lcov").unwrap();
+        let summary = registry.get("summary").unwrap();
+        let output_path = Utf8Path::new("coverage.lcov");
+
+        assert!(
+            ensure_no_stdout_reports(&[
+                (lcov, destination(lcov, None, output_path)),
+                (
+                    summary,
+                    destination(summary, Some(Utf8Path::new("summary.txt")), output_path)
+                ),
+            ])
+            .is_ok()
+        );
+        assert!(
+            ensure_no_stdout_reports(&[(summary, destination(summary, None, output_path))])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_contract_output_path_sanitizes_name() {
+        assert_eq!(
+            contract_output_path("coverage/coverage.lcov".into(), "HelloStarknet"),
+            "coverage/coverage.HelloStarknet.lcov"
+        );
+        assert_eq!(
+            contract_output_path("coverage.lcov".into(), "../pkg::Contract<T>"),
+            "coverage.___pkg__Contract_T_.lcov"
+        );
+    }
+}
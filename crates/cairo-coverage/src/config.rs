@@ -0,0 +1,181 @@
+//! Configuration of `cairo-coverage` read from the `[tool.cairo-coverage]` (or `[workspace.tool.cairo-coverage]`)
+//! section of `Scarb.toml`.
+use crate::args::run::LibfuncPreset;
+use crate::ui;
+use anyhow::{Context, Result};
+use scarb_metadata::Metadata;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+/// Name of the section in `[tool]`.
+const TOOL_NAME: &str = "cairo-coverage";
+
+/// Configuration from the `[tool.cairo-coverage]` section.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// The `[tool.cairo-coverage.unreliable-libfuncs]` section.
+    #[serde(default)]
+    pub unreliable_libfuncs: UnreliableLibfuncsConfig,
+}
+
+/// Libfunc policy from the `[tool.cairo-coverage.unreliable-libfuncs]` section.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct UnreliableLibfuncsConfig {
+    pub preset: Option<LibfuncPreset>,
+    /// Libfunc names or patterns treated as unreliable in addition to the preset.
+    #[serde(default)]
+    pub add: Vec<String>,
+    /// Libfunc names or patterns treated as reliable.
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// Load the [`Config`] from the `Scarb.toml` at the root of the workspace.
+/// It is read from the `[tool.cairo-coverage]` section of the root package or, in a virtual workspace,
+/// from the `[workspace.tool.cairo-coverage]` section.
+/// Returns the default config if the section is missing.
+///
+/// Sections of the other workspace members that differ from the root one are ignored with a warning.
+pub fn load(metadata: &Metadata) -> Result<Config> {
+    let manifest_path = &metadata.workspace.manifest_path;
+    let root_package = metadata
+        .packages
+        .iter()
+        .find(|package| package.manifest_path == *manifest_path);
+    let (section, tool_metadata) = match root_package {
+        Some(package) => ("tool", package.tool_metadata(TOOL_NAME).cloned()),
+        None => (
+            "workspace.tool",
+            load_workspace_tool_metadata(manifest_path.as_std_path())?,
+        ),
+    };
+
+    metadata
+        .packages
+        .iter()
+        .filter(|package| metadata.workspace.members.contains(&package.id))
+        .filter(|package| Some(*package) != root_package)
+        .filter(|package| {
+            package
+                .tool_metadata(TOOL_NAME)
+                .is_some_and(|package_metadata| Some(package_metadata) != tool_metadata.as_ref())
+        })
+        .for_each(|package| {
+            ui::warn(format!(
+                "ignoring [tool.{TOOL_NAME}] section of package {}, \
+                 the configuration is read only from the Scarb.toml at the root of the workspace",
+                package.name
+            ));
+        });
+
+    tool_metadata
+        .map(|tool_metadata| {
+            Config::deserialize(tool_metadata).context(format!(
+                "invalid [{section}.{TOOL_NAME}] section in Scarb.toml"
+            ))
+        })
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// Load the `[workspace.tool.cairo-coverage]` section of the `Scarb.toml` as JSON, like the `[tool]` sections
+/// of the packages in the Scarb metadata.
+fn load_workspace_tool_metadata(manifest_path: &Path) -> Result<Option<serde_json::Value>> {
+    let manifest = fs::read_to_string(manifest_path)
+        .with_context(|| format!("failed to read {}", manifest_path.display()))?
+        .parse::<DocumentMut>()
+        .with_context(|| format!("failed to parse {}", manifest_path.display()))?;
+
+    Ok(manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("tool"))
+        .and_then(|tool| tool.get(TOOL_NAME))
+        .and_then(item_to_json))
+}
+
+/// Convert a TOML [`Item`] to JSON. Returns `None` for empty items.
+fn item_to_json(item: &Item) -> Option<serde_json::Value> {
+    match item {
+        Item::None => None,
+        Item::Value(value) => value_to_json(value),
+        Item::Table(table) => Some(table_to_json(table)),
+        Item::ArrayOfTables(tables) => Some(tables.iter().map(table_to_json).collect()),
+    }
+}
+
+fn table_to_json(table: &Table) -> serde_json::Value {
+    table
+        .iter()
+        .filter_map(|(key, item)| Some((key.to_string(), item_to_json(item)?)))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+fn value_to_json(value: &Value) -> Option<serde_json::Value> {
+    Some(match value {
+        Value::String(string) => string.value().clone().into(),
+        Value::Integer(integer) => (*integer.value()).into(),
+        Value::Float(float) => serde_json::Number::from_f64(*float.value())?.into(),
+        Value::Boolean(boolean) => (*boolean.value()).into(),
+        Value::Datetime(datetime) => datetime.value().to_string().into(),
+        Value::Array(array) => array.iter().filter_map(value_to_json).collect(),
+        Value::InlineTable(table) => table
+            .iter()
+            .filter_map(|(key, value)| Some((key.to_string(), value_to_json(value)?)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use assert_fs::fixture::{FileWriteStr, PathChild};
+    use indoc::indoc;
+
+    #[test]
+    fn test_load_workspace_tool_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let manifest = temp_dir.child("Scarb.toml");
+        manifest
+            .write_str(indoc! {r#"
+                [workspace]
+                members = ["crates/*"]
+
+                [workspace.tool.cairo-coverage.unreliable-libfuncs]
+                preset = "strict"
+                add = ["felt252_is_zero"]
+            "#})
+            .unwrap();
+
+        let tool_metadata = load_workspace_tool_metadata(manifest.path())
+            .unwrap()
+            .unwrap();
+        let config = Config::deserialize(tool_metadata).unwrap();
+
+        assert_eq!(
+            config.unreliable_libfuncs.preset,
+            Some(LibfuncPreset::Strict)
+        );
+        assert_eq!(config.unreliable_libfuncs.add, ["felt252_is_zero"]);
+        assert!(config.unreliable_libfuncs.remove.is_empty());
+    }
+
+    #[test]
+    fn test_load_workspace_tool_metadata_without_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let manifest = temp_dir.child("Scarb.toml");
+        manifest.write_str("[workspace]\nmembers = []\n").unwrap();
+
+        assert!(
+            load_workspace_tool_metadata(manifest.path())
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -1,24 +1,55 @@
 use crate::args::CairoCoverageArgs;
 use anyhow::Result;
+use cairo_coverage_core::error::Error;
 
 use clap::Parser;
 use std::process::ExitCode;
 
 mod args;
 mod commands;
+mod config;
 mod ui;
 
 fn main() -> ExitCode {
     if let Err(error) = main_inner() {
-        ui::error(error);
-        ExitCode::FAILURE
+        report_error(&error);
+        exit_code(&error)
     } else {
         ExitCode::SUCCESS
     }
 }
 
 fn main_inner() -> Result<()> {
-    let CairoCoverageArgs { command } = CairoCoverageArgs::parse();
+    let CairoCoverageArgs {
+        command,
+        verbosity,
+        message_format,
+    } = CairoCoverageArgs::parse();
+    ui::init(message_format, verbosity.level_filter());
 
     commands::run(command)
 }
+
+/// Print the error with its kind and paths, if it is one of the errors of the core.
+fn report_error(error: &anyhow::Error) {
+    match error.downcast_ref::<Error>() {
+        Some(core_error) => ui::error_with_details(error, core_error.kind(), &core_error.paths()),
+        None => ui::error(error),
+    }
+}
+
+/// Exit code describing the error, so scripts can handle the common problems with the inputs.
+/// `1` is used for all other errors and `2` is used by `clap` for invalid arguments.
+fn exit_code(error: &anyhow::Error) -> ExitCode {
+    match error.downcast_ref::<Error>() {
+        Some(Error::ReadFile { .. }) => ExitCode::from(3),
+        Some(Error::InvalidTrace { .. }) => ExitCode::from(4),
+        Some(Error::MissingSierraFile { .. }) => ExitCode::from(5),
+        Some(Error::InvalidProgram { .. }) => ExitCode::from(6),
+        Some(Error::MissingDebugInfo { .. }) => ExitCode::from(7),
+        Some(Error::NoTraces) => ExitCode::from(8),
+        Some(Error::StaleInput { .. }) => ExitCode::from(9),
+        Some(Error::MissingProgram { .. }) => ExitCode::from(10),
+        Some(_) | None => ExitCode::FAILURE,
+    }
+}
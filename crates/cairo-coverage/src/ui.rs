@@ -1,16 +1,159 @@
 //! UI utilities for the Cairo coverage tool.
 //! All human-oriented messaging must use this module to communicate with the user.
 //! Messages should be lowercased and should not end with a period.
+//!
+//! Errors, warnings and diagnostics are printed to the standard error, so they do not mix with the reports
+//! printed to the standard output.
+//!
+//! With `--message-format json` every message is printed to the standard output as a single line JSON object instead,
+//! with the `type` field telling what kind of message it is.
+use crate::args::MessageFormat;
+use cairo_coverage_core::model::LineCounts;
+use camino::Utf8Path;
 use console::style;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde_json::json;
 use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// Format of the messages, set once by [`init`].
+static MESSAGE_FORMAT: OnceLock<MessageFormat> = OnceLock::new();
+
+/// Print the messages in the given [`MessageFormat`], including the diagnostics logged by `cairo-coverage`
+/// and its core up to the given level.
+pub fn init(message_format: MessageFormat, level: LevelFilter) {
+    MESSAGE_FORMAT
+        .set(message_format)
+        .unwrap_or_else(|_| unreachable!("the ui is initialized only once"));
+    log::set_logger(&UiLogger).unwrap_or_else(|_| unreachable!("the ui is initialized only once"));
+    log::set_max_level(level);
+}
 
 /// Print an error message.
 pub fn error(message: impl Display) {
-    let tag = style("error").red();
-    println!("{tag}: {message}");
+    error_with_details(message, "other", &[]);
+}
+
+/// Print an error message with the kind of the error and the paths of the inputs it is about.
+/// The details are only included in the JSON messages.
+pub fn error_with_details(message: impl Display, kind: &str, paths: &[&Utf8Path]) {
+    if is_json() {
+        print_json(&json!({
+            "type": "error",
+            "message": message.to_string(),
+            "kind": kind,
+            "paths": paths,
+        }));
+    } else {
+        let tag = style("error").red();
+        eprintln!("{tag}: {message}");
+    }
 }
 
 /// Print a message.
 pub fn msg(message: impl Display) {
+    if is_json() {
+        print_json(&json!({
+            "type": "message",
+            "message": message.to_string(),
+        }));
+    } else {
+        println!("{message}");
+    }
+}
+
+/// Print a warning message, unless warnings are disabled with `--quiet`.
+pub fn warn(message: impl Display) {
+    if log::max_level() >= LevelFilter::Warn {
+        warn_always(message);
+    }
+}
+
+/// Print a warning message even if warnings are disabled with `--quiet`,
+/// e.g. about inputs that are missing from the report.
+pub fn warn_always(message: impl Display) {
+    if is_json() {
+        print_json(&json!({
+            "type": "warning",
+            "message": message.to_string(),
+        }));
+    } else {
+        let tag = style("warning").yellow();
+        eprintln!("{tag}: {message}");
+    }
+}
+
+/// Print a diagnostic message, e.g. one enabled with `-v` or the time spent in each stage.
+pub fn diagnostic(message: impl Display) {
+    if is_json() {
+        print_json(&json!({
+            "type": "message",
+            "message": message.to_string(),
+        }));
+    } else {
+        eprintln!("{message}");
+    }
+}
+
+/// Print the path of a written report in the given format.
+/// It is always included in the JSON messages, but printed to humans only with `-v`.
+pub fn report_written(format: &str, path: &Utf8Path) {
+    if is_json() {
+        print_json(&json!({
+            "type": "report",
+            "format": format,
+            "path": path,
+        }));
+    } else if log::max_level() >= LevelFilter::Info {
+        println!("wrote {format} report to {path}");
+    }
+}
+
+/// Print the number of covered lines of the whole project.
+/// It is always included in the JSON messages, but printed to humans only with `-v`.
+pub fn summary(files: usize, line_counts: LineCounts) {
+    let LineCounts { found, hit } = line_counts;
+    let coverage = line_counts.percentage();
+    if is_json() {
+        print_json(&json!({
+            "type": "summary",
+            "files": files,
+            "lines_found": found,
+            "lines_hit": hit,
+            "coverage": coverage,
+        }));
+    } else if log::max_level() >= LevelFilter::Info {
+        println!("covered {hit} of {found} lines ({coverage:.2}%) in {files} file(s)");
+    }
+}
+
+/// Returns `true` if the messages are printed as JSON objects, so nothing else can be printed to the standard output.
+pub fn is_json() -> bool {
+    matches!(MESSAGE_FORMAT.get(), Some(MessageFormat::Json))
+}
+
+fn print_json(message: &serde_json::Value) {
     println!("{message}");
 }
+
+/// [`Log`] implementation printing the diagnostics of `cairo-coverage` crates, ignoring the ones of dependencies.
+struct UiLogger;
+
+impl Log for UiLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with("cairo_coverage")
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        match record.level() {
+            Level::Error => error(record.args()),
+            Level::Warn => warn(record.args()),
+            Level::Info | Level::Debug | Level::Trace => diagnostic(record.args()),
+        }
+    }
+
+    fn flush(&self) {}
+}
@@ -0,0 +1,13 @@
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::add;
+
+    #[test]
+    fn test_add() {
+        assert(add(2, 3) == 5, 'wrong sum');
+    }
+}
@@ -0,0 +1,34 @@
+#[starknet::interface]
+pub trait ICounter<TContractState> {
+    fn increment(ref self: TContractState, amount: u64);
+    fn get(self: @TContractState) -> u64;
+}
+
+#[starknet::contract]
+mod Counter {
+    #[storage]
+    struct Storage {
+        value: u64,
+    }
+
+    #[constructor]
+    fn constructor(ref self: ContractState, initial_value: u64) {
+        self.value.write(initial_value);
+    }
+
+    #[abi(embed_v0)]
+    impl CounterImpl of super::ICounter<ContractState> {
+        fn increment(ref self: ContractState, amount: u64) {
+            self.value.write(self.value.read() + amount);
+        }
+
+        fn get(self: @ContractState) -> u64 {
+            self.value.read()
+        }
+    }
+
+    #[external(v0)]
+    fn reset(ref self: ContractState) {
+        self.value.write(0);
+    }
+}
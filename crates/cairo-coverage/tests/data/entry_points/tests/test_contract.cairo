@@ -0,0 +1,13 @@
+use snforge_std::{ContractClassTrait, DeclareResultTrait, declare};
+use entry_points::{ICounterDispatcher, ICounterDispatcherTrait};
+
+#[test]
+fn test_increment() {
+    let contract = declare("Counter").unwrap().contract_class();
+    let (contract_address, _) = contract.deploy(@array![5]).unwrap();
+    let dispatcher = ICounterDispatcher { contract_address };
+
+    dispatcher.increment(2);
+
+    assert(dispatcher.get() == 7, 'Invalid value');
+}
@@ -0,0 +1,25 @@
+use crate::helpers::TestProject;
+
+#[test]
+fn simple_doctor() {
+    let output = TestProject::new("simple").run_doctor();
+
+    let stdout = output.stdout();
+    assert!(stdout.contains("scarb version: ok"));
+    assert!(stdout.contains("compiler configuration: ok"));
+    assert!(stdout.contains("built artifacts: ok"));
+}
+
+#[test]
+fn simple_doctor_missing_inlining_strategy() {
+    let output = TestProject::new("simple")
+        .replace_in_manifest(r#"inlining-strategy= "avoid""#, "")
+        .expect_exit_code(1)
+        .run_doctor();
+
+    let stderr = output.stderr();
+    assert!(stderr.contains("the `dev` profile does not set `inlining-strategy = \"avoid\"`"));
+    assert!(stderr.contains("fix: add the following to"));
+    assert!(stderr.contains("[profile.dev.cairo]\ninlining-strategy = \"avoid\""));
+    assert!(stderr.contains("found 1 problem(s) with the project setup"));
+}
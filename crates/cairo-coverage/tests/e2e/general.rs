@@ -1,6 +1,8 @@
-use crate::helpers::{TestProject, scarb_version};
+use crate::helpers::{Runner, TestProject, scarb_version};
 use assert_fs::fixture::PathChild;
 use semver::Version;
+use std::collections::HashMap;
+use std::path::Path;
 
 #[test]
 fn simple() {
@@ -21,6 +23,90 @@ fn simple_with_output_path() {
     );
 }
 
+#[test]
+fn simple_keep_going_with_corrupted_trace() {
+    TestProject::new("simple")
+        .extra_trace_file("corrupted.json", "{")
+        .coverage_args(&["--keep-going"])
+        .run()
+        .output_same_as_in_file("simple.lcov");
+}
+
+#[test]
+fn simple_corrupted_trace_exit_code() {
+    let output = TestProject::new("simple")
+        .extra_trace_file("corrupted.json", "{")
+        .expect_exit_code(4)
+        .run_coverage_only();
+
+    assert!(
+        output
+            .stderr()
+            .contains("failed to deserialize trace file at path:")
+    );
+}
+
+#[test]
+fn simple_missing_sierra_file_exit_code() {
+    let output = TestProject::new("simple")
+        .remove_after_build("target")
+        .expect_exit_code(5)
+        .run_coverage_only();
+
+    let stderr = output.stderr();
+    assert!(stderr.contains("does not exist, attempted locations:"));
+    assert!(stderr.contains("sierra.json"));
+}
+
+#[test]
+fn simple_keep_going_with_missing_sierra_file() {
+    let output = TestProject::new("simple")
+        .remove_after_build("target")
+        .coverage_args(&["--keep-going"])
+        .run_coverage_only();
+
+    let stderr = output.stderr();
+    assert!(stderr.contains("skipped program"));
+    assert!(stderr.contains("does not exist, attempted locations:"));
+}
+
+#[test]
+fn simple_keep_going_quiet_prints_skipped_inputs() {
+    let output = TestProject::new("simple")
+        .remove_after_build("target")
+        .coverage_args(&["--keep-going", "--quiet"])
+        .run_coverage_only();
+
+    assert!(output.stderr().contains("skipped program"));
+}
+
+#[test]
+fn simple_corrupted_trace_json_message() {
+    let output = TestProject::new("simple")
+        .coverage_args(&["--message-format", "json"])
+        .extra_trace_file("corrupted.json", "{")
+        .expect_exit_code(4)
+        .run_coverage_only();
+
+    let error: serde_json::Value = serde_json::from_str(output.stdout().trim_end()).unwrap();
+    assert_eq!(error["type"], "error");
+    assert_eq!(error["kind"], "invalid_trace");
+    assert!(
+        error["paths"][0]
+            .as_str()
+            .unwrap()
+            .ends_with("corrupted.json")
+    );
+}
+
+#[test]
+fn simple_relative_paths() {
+    TestProject::new("simple")
+        .coverage_args(&["--relative-paths"])
+        .run()
+        .output_same_as_in_file("simple_relative_paths.lcov");
+}
+
 #[test]
 fn scarb_template() {
     TestProject::new("scarb_template")
@@ -75,6 +161,334 @@ fn snforge_template() {
         .output_same_as_in_file(file);
 }
 
+#[test]
+fn snforge_template_summary() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&["--format", "summary"])
+        .run_coverage_only();
+    let stdout = output.stdout();
+
+    assert!(stdout.contains("entry points of contract HelloStarknet"));
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.starts_with("external  increase_balance  2 "))
+    );
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.starts_with("external  get_balance       3 "))
+    );
+}
+
+#[test]
+fn entry_points_summary() {
+    let output = TestProject::new("entry_points")
+        .coverage_args(&["--format", "summary"])
+        .run_coverage_only();
+
+    // Columns: type, name, invocations, lines hit, lines found, coverage.
+    let rows: Vec<Vec<&str>> = output
+        .stdout()
+        .split("entry points of contract Counter")
+        .nth(1)
+        .unwrap()
+        .lines()
+        .skip(2)
+        .take_while(|line| !line.is_empty())
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    let counts = |name: &str| -> (String, usize, usize) {
+        let row = rows
+            .iter()
+            .find(|row| row[1] == name)
+            .unwrap_or_else(|| panic!("{name} not found in {rows:?}"));
+        (
+            row[2].to_string(),
+            row[3].parse().unwrap(),
+            row[4].parse().unwrap(),
+        )
+    };
+
+    // Wrappers of functions inside an embedded impl are mapped to the implementing function.
+    for name in ["increment", "get"] {
+        let (invocations, lines_hit, lines_found) = counts(name);
+        assert_eq!(invocations, "1");
+        assert!(lines_found > 0 && lines_hit == lines_found, "{name}");
+    }
+    // Wrappers of standalone functions are mapped to the function itself.
+    let (invocations, lines_hit, lines_found) = counts("constructor");
+    assert_eq!(invocations, "1");
+    assert!(lines_found > 0 && lines_hit == lines_found);
+    let (invocations, lines_hit, lines_found) = counts("reset");
+    assert_eq!(invocations, "0");
+    assert!(lines_found > 0 && lines_hit == 0);
+}
+
+#[test]
+fn snforge_template_sierra() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&["--format", "sierra"])
+        .run_coverage_only();
+    let stdout = output.stdout();
+
+    assert!(stdout.contains("statements of "));
+    assert!(stdout.lines().any(|line| line.ends_with("  included")));
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.ends_with("  excluded: unreliable libfunc"))
+    );
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.ends_with("  excluded: test function"))
+    );
+}
+
+#[test]
+fn snforge_template_strict_libfunc_preset() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&["--format", "sierra", "--libfunc-preset", "strict"])
+        .run_coverage_only();
+    let stdout = output.stdout();
+
+    assert!(stdout.lines().any(|line| line.ends_with("  included")));
+    assert!(!stdout.contains("excluded: unreliable libfunc"));
+}
+
+#[test]
+fn snforge_template_exclude_non_executable() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&[
+            "--format",
+            "sierra",
+            "--libfunc-preset",
+            "strict",
+            "--exclude-non-executable",
+        ])
+        .run_coverage_only();
+    let stdout = output.stdout();
+
+    // Statements like `branch_align` or `struct_deconstruct` are compiled to no CASM instructions.
+    assert!(stdout.lines().any(|line| {
+        line.contains("src/lib.cairo") && line.ends_with("  excluded: no casm instructions")
+    }));
+    assert!(!stdout.contains("excluded: unreliable libfunc"));
+}
+
+#[test]
+fn snforge_template_no_truncation_is_stable() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&["--no-truncation"])
+        .run_coverage_only();
+    let project_dir = output.dir().canonicalize().unwrap();
+    let hit_counts = line_hit_counts(&output.read_output(), &project_dir);
+
+    // `get_balance` is called three times and `increase_balance` twice, the second time with 0, so it panics
+    // before reaching the write. `deploy_contract` is called once by each test.
+    // Lines of the code generated for the contract and its dispatchers depend on the Scarb version and are skipped.
+    let expected = [
+        ("src/lib.cairo", 17, 2),
+        ("src/lib.cairo", 18, 1),
+        ("src/lib.cairo", 22, 3),
+        ("tests/test_contract.cairo", 11, 2),
+        ("tests/test_contract.cairo", 12, 2),
+    ];
+    for (file, line, hit_count) in expected {
+        assert_eq!(
+            hit_counts.get(&(file.to_string(), line)),
+            Some(&hit_count),
+            "hit count of {file}:{line}"
+        );
+    }
+}
+
+/// Hit counts from the `DA` records of the LCOV report, keyed by the source file path relative to the project
+/// directory and the line number.
+fn line_hit_counts(lcov: &str, project_dir: &Path) -> HashMap<(String, usize), usize> {
+    let mut hit_counts = HashMap::new();
+    let mut source_file = String::new();
+    for line in lcov.lines() {
+        if let Some(path) = line.strip_prefix("SF:") {
+            source_file = Path::new(path)
+                .strip_prefix(project_dir)
+                .unwrap()
+                .display()
+                .to_string();
+        } else if let Some((line_number, hit_count)) =
+            line.strip_prefix("DA:").and_then(|da| da.split_once(','))
+        {
+            hit_counts.insert(
+                (source_file.clone(), line_number.parse().unwrap()),
+                hit_count.parse().unwrap(),
+            );
+        }
+    }
+    hit_counts
+}
+
+#[test]
+fn snforge_template_explain() {
+    let output = TestProject::new("snforge_template")
+        .explain("src/lib.cairo:18")
+        .run_coverage_only();
+    let stdout = output.stdout();
+
+    assert!(stdout.starts_with("src/lib.cairo:18 is covered"));
+    assert!(stdout.contains("HelloStarknet::increase_balance"));
+}
+
+#[test]
+fn snforge_template_multiple_formats() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&[
+            "--format",
+            "lcov",
+            "--format",
+            "json=report.json",
+            "--format",
+            "summary",
+        ])
+        .run_coverage_only();
+
+    assert!(output.read_output().contains("end_of_record"));
+    assert!(
+        output
+            .stdout()
+            .contains("files covered through HelloStarknet")
+    );
+
+    let report = std::fs::read_to_string(output.dir().child("report.json")).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+    assert!(!report["files"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn snforge_template_timings() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&["--timings"])
+        .run_coverage_only();
+
+    assert!(output.read_output().contains("end_of_record"));
+    // The timings are diagnostics, so they never mix with the reports printed to stdout.
+    assert!(!output.stdout().contains("timings:"));
+    let stderr = output.stderr();
+    for stage in [
+        "loading",
+        "casm compilation",
+        "filtering",
+        "statement mapping",
+        "merging",
+        "formatting",
+    ] {
+        assert!(stderr.contains(&format!("- {stage}: ")), "{stage} missing");
+    }
+}
+
+#[test]
+fn snforge_template_verbose() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&["-vv"])
+        .run_coverage_only();
+
+    let stderr = output.stderr();
+    assert!(stderr.contains("loaded 1 trace(s) executing"));
+    assert!(stderr.contains("statement(s) included in the report"));
+    assert!(stderr.contains("statement(s) excluded as test function"));
+    assert!(stderr.contains("covered line(s)"));
+}
+
+#[test]
+fn snforge_template_json_messages() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&[
+            "--message-format",
+            "json",
+            "-vv",
+            "--format",
+            "lcov",
+            "--format",
+            "summary=summary.txt",
+        ])
+        .run_coverage_only();
+
+    let messages: Vec<serde_json::Value> = output
+        .stdout()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(messages.iter().any(|message| {
+        message["type"] == "report"
+            && message["format"] == "lcov"
+            && message["path"].as_str().unwrap().ends_with("coverage.lcov")
+    }));
+    assert!(messages.iter().any(|message| {
+        message["type"] == "summary" && message["lines_hit"].as_u64().unwrap() > 0
+    }));
+}
+
+#[test]
+fn snforge_template_json_messages_reject_stdout_reports() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&["--message-format", "json", "--format", "summary"])
+        .expect_exit_code(1)
+        .run_coverage_only();
+
+    let messages: Vec<serde_json::Value> = output
+        .stdout()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(messages.iter().any(|message| {
+        message["type"] == "error"
+            && message["message"]
+                .as_str()
+                .unwrap()
+                .contains("format summary cannot be printed to the standard output")
+    }));
+}
+
+#[test]
+fn snforge_template_json() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&["--format", "json"])
+        .run_coverage_only();
+    let report: serde_json::Value = serde_json::from_str(output.stdout()).unwrap();
+
+    let syscalls = report["syscalls"].as_array().unwrap();
+    let storage_write = syscalls
+        .iter()
+        .find(|syscall| {
+            syscall["contract"] == "HelloStarknet"
+                && syscall["function"] == "increase_balance"
+                && syscall["syscall"] == "StorageWrite"
+        })
+        .unwrap();
+    assert_eq!(storage_write["call_count"], 1);
+    assert_eq!(storage_write["tests"].as_array().unwrap().len(), 1);
+
+    let calls = report["calls"].as_array().unwrap();
+    assert!(calls.iter().any(|call| {
+        call["kind"] == "call"
+            && call["called_contract"] == "HelloStarknet"
+            && call["called_function"] == "get_balance"
+    }));
+}
+
+#[test]
+fn snforge_template_split_by_contract() {
+    let output = TestProject::new("snforge_template")
+        .coverage_args(&["--split-by-contract"])
+        .run();
+    let contract_report =
+        std::fs::read_to_string(output.dir().child("coverage.HelloStarknet.lcov")).unwrap();
+
+    assert!(contract_report.contains("SF:"));
+    assert!(contract_report.contains("HelloStarknetImpl::increase_balance"));
+    assert!(!contract_report.contains("test_increase_balance"));
+}
+
 #[test]
 #[cfg(feature = "allows-excluding-macros")]
 fn snforge_template_macros_not_included() {
@@ -83,3 +497,72 @@ fn snforge_template_macros_not_included() {
         .run()
         .output_same_as_in_file("snforge_template_macros_not_included.lcov");
 }
+
+#[test]
+fn scarb_execute() {
+    let output = TestProject::new("scarb_execute")
+        .runner(Runner::ScarbExecute)
+        .run();
+    let lcov = output.read_output();
+
+    assert!(lcov.contains("FNDA:1,scarb_execute::fib"));
+    assert!(lcov.contains("FNDA:0,scarb_execute::unused"));
+}
+
+#[test]
+fn scarb_execute_vm_trace() {
+    let output = TestProject::new("scarb_execute")
+        .runner(Runner::CairoVm)
+        .run();
+    let lcov = output.read_output();
+
+    assert!(lcov.contains("FNDA:1,scarb_execute::fib"));
+    assert!(lcov.contains("FNDA:0,scarb_execute::unused"));
+}
+
+#[test]
+fn cairo_test_functions_are_excluded() {
+    let output = TestProject::new("cairo_test_project")
+        .runner(Runner::CairoTest)
+        .run();
+    let lcov = output.read_output();
+
+    assert!(lcov.contains("FNDA:0,cairo_test_project::add"));
+    assert!(!lcov.contains("test_add"));
+}
+
+#[test]
+fn snforge_template_modified_source_warning() {
+    let output = TestProject::new("snforge_template")
+        .modify_source_file_after_build("src/lib.cairo", "")
+        .run_coverage_only();
+
+    let stderr = output.stderr();
+    assert!(stderr.contains("warning: stale input at path:"));
+    assert!(stderr.contains("lib.cairo"));
+}
+
+#[test]
+fn snforge_template_source_edited_after_build_keeping_lines() {
+    let source = std::fs::read_to_string("tests/data/snforge_template/src/lib.cairo").unwrap();
+    let output = TestProject::new("snforge_template")
+        .modify_source_file_after_build("src/lib.cairo", &format!("{source}\n// edited\n"))
+        .run_coverage_only();
+
+    assert!(
+        output
+            .stderr()
+            .contains("the source file was modified after the program was built")
+    );
+}
+
+#[test]
+fn snforge_template_modified_source_strict() {
+    let output = TestProject::new("snforge_template")
+        .modify_source_file_after_build("src/lib.cairo", "")
+        .coverage_args(&["--strict"])
+        .expect_exit_code(9)
+        .run_coverage_only();
+
+    assert!(output.stderr().contains("error: stale input at path:"));
+}
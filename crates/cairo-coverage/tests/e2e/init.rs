@@ -0,0 +1,31 @@
+use crate::helpers::TestProject;
+use assert_fs::fixture::PathChild;
+use std::fs;
+
+#[test]
+fn simple_init() {
+    let output = TestProject::new("simple")
+        .coverage_args(&["--ignore-file"])
+        .run_init();
+
+    let manifest = fs::read_to_string(output.dir().child("Scarb.toml")).unwrap();
+    assert!(manifest.starts_with("[package]\nname = \"simple\""));
+    assert!(manifest.contains("[profile.coverage]\ninherits = \"dev\""));
+    assert!(manifest.contains("[profile.coverage.cairo]"));
+    assert!(output.dir().child(".cairo-coverage-ignore").exists());
+    assert!(
+        output
+            .stdout()
+            .contains("snforge test --coverage --profile coverage")
+    );
+}
+
+#[test]
+fn simple_init_is_idempotent() {
+    let output = TestProject::new("simple")
+        .coverage_args(&["--profile", "dev"])
+        .run_init();
+
+    assert!(output.stdout().contains("the `dev` profile in"));
+    assert!(output.stdout().contains("is already configured"));
+}
@@ -1,2 +1,4 @@
 mod cairo_coverage_ignore;
+mod doctor;
 mod general;
+mod init;
@@ -2,4 +2,4 @@ mod scarb_version;
 mod test_project;
 
 pub use scarb_version::scarb_version;
-pub use test_project::TestProject;
+pub use test_project::{Runner, TestProject};
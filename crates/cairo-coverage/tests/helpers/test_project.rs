@@ -1,15 +1,42 @@
 use assert_fs::TempDir;
 use assert_fs::fixture::PathCopy;
-use cairo_coverage_test_utils::read_files_from_dir;
+use cairo_coverage_test_utils::{Utf8PathBufConversion, read_files_from_dir};
 use camino::Utf8PathBuf;
 use snapbox::cmd;
 use snapbox::cmd::Command as SnapboxCommand;
 use std::fs;
 use std::path::PathBuf;
+use walkdir::WalkDir;
+
+/// Tool used to run the test project and generate the trace files.
+pub enum Runner {
+    /// `snforge test`, the trace files are saved in the `snfoundry_trace` directory.
+    Snforge,
+    /// `scarb execute`, the trace files are saved in the `target/execute` directory.
+    ScarbExecute,
+    /// `scarb execute` saving the binary trace written by the Cairo VM, passed with `--vm-trace`.
+    /// The program and its offset are taken from the trace data saved in the same run.
+    CairoVm,
+    /// `scarb build --test` building the `cairo-test` test program in the `target/dev` directory.
+    /// `cairo-test` does not save trace data, so the program is passed with an empty Cairo VM trace.
+    CairoTest,
+}
 
 pub struct TestProject {
     dir: TempDir,
+    runner: Runner,
+    /// Subcommand of `cairo-coverage` with the arguments preceding the trace files.
+    subcommand: Vec<String>,
     coverage_args: Vec<String>,
+    extra_trace_files: Vec<(String, String)>,
+    /// Source files overwritten after the trace files are generated, as paths relative to the project.
+    modified_source_files: Vec<(String, String)>,
+    /// Files and directories removed after the trace files are generated, as paths relative to the project.
+    removed_paths: Vec<String>,
+    /// Exit code `cairo-coverage` is expected to finish with.
+    exit_code: i32,
+    coverage_stdout: String,
+    coverage_stderr: String,
 }
 
 impl TestProject {
@@ -24,7 +51,15 @@ impl TestProject {
 
         Self {
             dir,
+            runner: Runner::Snforge,
+            subcommand: vec!["run".to_string()],
             coverage_args: vec![],
+            extra_trace_files: vec![],
+            modified_source_files: vec![],
+            removed_paths: vec![],
+            exit_code: 0,
+            coverage_stdout: String::new(),
+            coverage_stderr: String::new(),
         }
     }
 
@@ -44,29 +79,166 @@ impl TestProject {
         self.generate_trace_files().run_coverage().output()
     }
 
+    /// Run `cairo-coverage` without checking the `lcov` report with `genhtml`,
+    /// e.g. because the run is expected to fail or writes the report in another format.
+    pub fn run_coverage_only(self) -> TestProjectOutput {
+        self.generate_trace_files().run_coverage().output()
+    }
+
+    /// Build the project with the runner and run `cairo-coverage doctor` instead of computing the coverage.
+    pub fn run_doctor(self) -> TestProjectOutput {
+        self.generate_trace_files()
+            .run_subcommand("doctor")
+            .output()
+    }
+
+    /// Run `cairo-coverage init` in the project without building it.
+    pub fn run_init(self) -> TestProjectOutput {
+        self.run_subcommand("init").output()
+    }
+
+    pub fn runner(mut self, runner: Runner) -> Self {
+        self.runner = runner;
+        self
+    }
+
+    /// Run `cairo-coverage explain` for the given source line instead of `cairo-coverage run`.
+    pub fn explain(mut self, source_line: &str) -> Self {
+        self.subcommand = vec!["explain".to_string(), source_line.to_string()];
+        self
+    }
+
+    /// Expect `cairo-coverage` to fail with the given exit code.
+    pub fn expect_exit_code(mut self, exit_code: i32) -> Self {
+        self.exit_code = exit_code;
+        self
+    }
+
     pub fn coverage_args(mut self, args: &[&str]) -> Self {
         self.coverage_args = args.iter().map(ToString::to_string).collect();
         self
     }
 
+    /// Replace the text in the `Scarb.toml` of the project, e.g. to remove an entry.
+    pub fn replace_in_manifest(self, from: &str, to: &str) -> Self {
+        let manifest_path = self.dir.path().join("Scarb.toml");
+        let manifest = fs::read_to_string(&manifest_path).unwrap();
+        assert!(manifest.contains(from), "{from} not found in Scarb.toml");
+        fs::write(manifest_path, manifest.replace(from, to)).unwrap();
+        self
+    }
+
     pub fn create_cairo_coverage_ignore(self, content: &str) -> Self {
         fs::write(self.dir.path().join(".cairo-coverage-ignore"), content).unwrap();
         self
     }
 
+    /// Add a trace file with the given content next to the ones generated by `snforge`.
+    pub fn extra_trace_file(mut self, file_name: &str, content: &str) -> Self {
+        self.extra_trace_files
+            .push((file_name.to_string(), content.to_string()));
+        self
+    }
+
+    /// Overwrite the source file with the given content after the trace files are generated.
+    pub fn modify_source_file_after_build(mut self, path: &str, content: &str) -> Self {
+        self.modified_source_files
+            .push((path.to_string(), content.to_string()));
+        self
+    }
+
+    /// Remove the file or directory after the trace files are generated, e.g. to make the programs unavailable.
+    pub fn remove_after_build(mut self, path: &str) -> Self {
+        self.removed_paths.push(path.to_string());
+        self
+    }
+
     fn generate_trace_files(self) -> Self {
-        SnapboxCommand::new("snforge")
-            .arg("test")
-            .arg("--save-trace-data")
-            .current_dir(&self.dir)
-            .assert()
-            .success();
+        let command = match self.runner {
+            Runner::Snforge => SnapboxCommand::new("snforge")
+                .arg("test")
+                .arg("--save-trace-data"),
+            Runner::ScarbExecute => SnapboxCommand::new("scarb")
+                .arg("execute")
+                .arg("--save-profiler-trace-data"),
+            Runner::CairoVm => SnapboxCommand::new("scarb")
+                .arg("execute")
+                .arg("--save-profiler-trace-data")
+                .arg("--output")
+                .arg("standard"),
+            Runner::CairoTest => SnapboxCommand::new("scarb").arg("build").arg("--test"),
+        };
+        command.current_dir(&self.dir).assert().success();
         self
     }
 
     fn find_trace_files(&self) -> Vec<Utf8PathBuf> {
-        let trace_path = self.dir.path().join("snfoundry_trace");
-        read_files_from_dir(trace_path)
+        match self.runner {
+            Runner::Snforge => {
+                let trace_path = self.dir.path().join("snfoundry_trace");
+                for (file_name, content) in &self.extra_trace_files {
+                    fs::write(trace_path.join(file_name), content).unwrap();
+                }
+                read_files_from_dir(trace_path)
+            }
+            // Each execution is saved in its own directory together with other outputs.
+            Runner::ScarbExecute => WalkDir::new(self.dir.path().join("target").join("execute"))
+                .into_iter()
+                .flatten()
+                .map(|entry| entry.path().to_utf8_path_buf())
+                .filter(|path| {
+                    path.extension() == Some("json")
+                        && path.file_stem().is_some_and(|stem| stem.contains("trace"))
+                })
+                .collect(),
+            Runner::CairoVm | Runner::CairoTest => Vec::new(),
+        }
+    }
+
+    /// Arguments passing the traces that are not trace files, e.g. the empty Cairo VM trace of the `cairo-test` program.
+    fn vm_trace_args(&self) -> Vec<String> {
+        match self.runner {
+            Runner::Snforge | Runner::ScarbExecute => Vec::new(),
+            Runner::CairoVm => WalkDir::new(self.dir.path().join("target").join("execute"))
+                .into_iter()
+                .flatten()
+                .map(|entry| entry.path().to_utf8_path_buf())
+                .filter(|path| path.file_name() == Some("trace.bin"))
+                .flat_map(|trace_path| {
+                    let execution_info =
+                        profiler_trace_next_to(&trace_path)["cairo_execution_info"].clone();
+                    let program_path = execution_info["source_sierra_path"]
+                        .as_str()
+                        .unwrap()
+                        .to_string();
+                    // Without the offset, the program is expected to be wrapped in a header ending the execution.
+                    let program_offset = execution_info["casm_level_info"]["program_offset"]
+                        .as_u64()
+                        .map(|program_offset| format!("@{program_offset}"))
+                        .unwrap_or_default();
+                    [
+                        "--vm-trace".to_string(),
+                        format!("{trace_path}={program_path}{program_offset}"),
+                    ]
+                })
+                .collect(),
+            Runner::CairoTest => {
+                let trace_path = self.dir.path().join("trace.bin").to_utf8_path_buf();
+                fs::write(&trace_path, []).unwrap();
+                WalkDir::new(self.dir.path().join("target").join("dev"))
+                    .into_iter()
+                    .flatten()
+                    .map(|entry| entry.path().to_utf8_path_buf())
+                    .filter(|path| path.as_str().ends_with(".test.sierra.json"))
+                    .flat_map(|program_path| {
+                        [
+                            "--vm-trace".to_string(),
+                            format!("{trace_path}={program_path}"),
+                        ]
+                    })
+                    .collect()
+            }
+        }
     }
 
     fn output_lcov_path(&self) -> PathBuf {
@@ -81,15 +253,47 @@ impl TestProject {
         self.dir.path().join(output_file_name)
     }
 
-    fn run_coverage(self) -> Self {
+    fn run_coverage(mut self) -> Self {
+        for (path, content) in &self.modified_source_files {
+            fs::write(self.dir.path().join(path), content).unwrap();
+        }
+        for path in &self.removed_paths {
+            let path = self.dir.path().join(path);
+            if path.is_dir() {
+                fs::remove_dir_all(path).unwrap();
+            } else {
+                fs::remove_file(path).unwrap();
+            }
+        }
         let trace_files = self.find_trace_files();
-        SnapboxCommand::new(cmd::cargo_bin!("cairo-coverage"))
-            .arg("run")
+        let vm_trace_args = self.vm_trace_args();
+        let output = SnapboxCommand::new(cmd::cargo_bin!("cairo-coverage"))
+            .args(&self.subcommand)
             .args(&trace_files)
+            .args(&vm_trace_args)
             .args(&self.coverage_args)
             .current_dir(&self.dir)
             .assert()
-            .success();
+            .code(self.exit_code)
+            .get_output()
+            .clone();
+        self.coverage_stdout = String::from_utf8(output.stdout).unwrap();
+        self.coverage_stderr = String::from_utf8(output.stderr).unwrap();
+        self
+    }
+
+    /// Run the subcommand of `cairo-coverage` that does not take trace files.
+    fn run_subcommand(mut self, subcommand: &str) -> Self {
+        let output = SnapboxCommand::new(cmd::cargo_bin!("cairo-coverage"))
+            .arg(subcommand)
+            .args(&self.coverage_args)
+            .current_dir(&self.dir)
+            .assert()
+            .code(self.exit_code)
+            .get_output()
+            .clone();
+        self.coverage_stdout = String::from_utf8(output.stdout).unwrap();
+        self.coverage_stderr = String::from_utf8(output.stderr).unwrap();
         self
     }
 
@@ -104,6 +308,18 @@ impl TestProject {
     }
 }
 
+/// Read the trace data saved by `scarb execute` in the same directory as the given binary trace.
+fn profiler_trace_next_to(trace_path: &Utf8PathBuf) -> serde_json::Value {
+    let profiler_trace_path = read_files_from_dir(trace_path.parent().unwrap())
+        .into_iter()
+        .find(|path| {
+            path.extension() == Some("json")
+                && path.file_stem().is_some_and(|stem| stem.contains("trace"))
+        })
+        .unwrap();
+    serde_json::from_str(&fs::read_to_string(profiler_trace_path).unwrap()).unwrap()
+}
+
 pub struct TestProjectOutput(TestProject);
 
 impl TestProjectOutput {
@@ -129,6 +345,16 @@ impl TestProjectOutput {
         fs::read_to_string(self.0.output_lcov_path()).unwrap()
     }
 
+    /// Standard output of the `cairo-coverage run` command.
+    pub fn stdout(&self) -> &str {
+        &self.0.coverage_stdout
+    }
+
+    /// Standard error of the `cairo-coverage run` command, with the errors, warnings and diagnostics.
+    pub fn stderr(&self) -> &str {
+        &self.0.coverage_stderr
+    }
+
     pub fn dir(&self) -> &TempDir {
         &self.0.dir
     }